@@ -0,0 +1,434 @@
+use crate::recording::*;
+use voxel_std_types::recording::*;
+
+/// The bytes at the start of every recording.
+const SIGNATURE: [u8; 4] = *b"VXIR";
+/// The version of the format produced by the writer.
+const VERSION: u16 = 2;
+/// The earliest version of the format that may be read, which stored the scroll delta as two `f32`s.
+const FLOAT_SCROLL_VERSION: u16 = 1;
+
+/// Produces the binary representation of a recording. The layout is:
+///
+/// - the signature `VXIR`
+/// - the format version, as a little-endian `u16`
+/// - a byte that is one if the start pose follows, and zero otherwise
+/// - the start position and rotation, as seven little-endian `f32`s
+/// - the number of streams, as a varint
+/// - for each stream, the length and UTF-8 bytes of its name, the number of analog actions,
+///   the number of digital actions, and the number of frames, all as varints, followed by the frames
+///
+/// Each frame holds the frame duration and pointer delta as three `f32`s, and the scroll delta as two little-endian
+/// `i32`s, then a byte that is one if three `f32`s of pointer direction follow. Next comes a bitmask of the analog actions that are nonzero,
+/// followed by their values as `f32`s. Finally, the digital actions are packed two bits apiece, with the
+/// held bit first. All varints are unsigned LEB128, and bits are packed from the least significant bit.
+pub fn encode(recording: &InputRecording) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&SIGNATURE);
+    result.extend_from_slice(&VERSION.to_le_bytes());
+
+    match recording.start {
+        Some(start) => {
+            result.push(1);
+            write_f32s(&mut result, &start.position);
+            write_f32s(&mut result, &start.rotation);
+        },
+        None => result.push(0)
+    }
+
+    write_varint(&mut result, recording.streams.len() as u64);
+    for stream in &recording.streams {
+        write_varint(&mut result, stream.name.len() as u64);
+        result.extend_from_slice(stream.name.as_bytes());
+        write_varint(&mut result, stream.analog_count as u64);
+        write_varint(&mut result, stream.digital_count as u64);
+        write_varint(&mut result, stream.frames.len() as u64);
+
+        for frame in &stream.frames {
+            encode_frame(&mut result, stream, frame);
+        }
+    }
+
+    result
+}
+
+/// Reads a recording from its binary representation, rejecting malformed data.
+pub fn decode(bytes: &[u8]) -> Result<InputRecording, RecordingError> {
+    let mut reader = ByteReader { bytes };
+
+    if reader.read_bytes(SIGNATURE.len())? != SIGNATURE {
+        return Err(RecordingError::InvalidSignature);
+    }
+
+    let version = u16::from_le_bytes([reader.read_byte()?, reader.read_byte()?]);
+    if !(FLOAT_SCROLL_VERSION..=VERSION).contains(&version) {
+        return Err(RecordingError::UnsupportedVersion(version));
+    }
+
+    let start = match reader.read_byte()? {
+        0 => None,
+        _ => Some(StartPose {
+            position: reader.read_f32s()?,
+            rotation: reader.read_f32s()?
+        })
+    };
+
+    let stream_count = reader.read_count()?;
+    let mut streams = Vec::new();
+    for _ in 0..stream_count {
+        let name_length = reader.read_count()?;
+        let name = String::from_utf8(reader.read_bytes(name_length)?.to_vec()).map_err(|_| RecordingError::InvalidName)?;
+        let analog_count = reader.read_count()?;
+        let digital_count = reader.read_count()?;
+        let frame_count = reader.read_count()?;
+
+        let mut stream = InputStream {
+            analog_count,
+            digital_count,
+            frames: Vec::new(),
+            name
+        };
+
+        for _ in 0..frame_count {
+            let frame = decode_frame(&mut reader, &stream, version)?;
+            stream.frames.push(frame);
+        }
+
+        streams.push(stream);
+    }
+
+    if reader.bytes.is_empty() {
+        Ok(InputRecording { start, streams })
+    }
+    else {
+        Err(RecordingError::TrailingData)
+    }
+}
+
+/// Appends a single frame, whose actions must match the counts of its stream.
+fn encode_frame(output: &mut Vec<u8>, stream: &InputStream, frame: &InputFrame) {
+    write_f32s(output, &[frame.delta_time]);
+    write_f32s(output, &frame.pointer_delta);
+    for value in frame.scroll_delta {
+        output.extend_from_slice(&value.to_le_bytes());
+    }
+
+    match frame.pointer_direction {
+        Some(direction) => {
+            output.push(1);
+            write_f32s(output, &direction);
+        },
+        None => output.push(0)
+    }
+
+    let analog = &frame.analog[..stream.analog_count];
+    write_bits(output, analog.iter().map(|&x| x != 0.0));
+    for &value in analog.iter().filter(|&&x| x != 0.0) {
+        write_f32s(output, &[value]);
+    }
+
+    write_bits(output, frame.digital[..stream.digital_count].iter().flat_map(|x| [x.held, x.pressed]));
+}
+
+/// Reads a single frame with the action counts of its stream, in the layout of the given format version.
+fn decode_frame(reader: &mut ByteReader, stream: &InputStream, version: u16) -> Result<InputFrame, RecordingError> {
+    let [delta_time] = reader.read_f32s()?;
+    let pointer_delta = reader.read_f32s()?;
+    let scroll_delta = if version == FLOAT_SCROLL_VERSION {
+        reader.read_f32s::<2>()?.map(|x| x.round() as i32)
+    }
+    else {
+        [reader.read_i32()?, reader.read_i32()?]
+    };
+
+    let pointer_direction = match reader.read_byte()? {
+        0 => None,
+        _ => Some(reader.read_f32s()?)
+    };
+
+    let nonzero = reader.read_bits(stream.analog_count)?;
+    let analog = nonzero.into_iter()
+        .map(|x| if x { reader.read_f32s().map(|[value]| value) } else { Ok(0.0) })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let bits = reader.read_bits(2 * stream.digital_count)?;
+    let digital = bits.chunks_exact(2).map(|x| DigitalSample { held: x[0], pressed: x[1] }).collect();
+
+    Ok(InputFrame {
+        analog,
+        delta_time,
+        digital,
+        pointer_delta,
+        pointer_direction,
+        scroll_delta
+    })
+}
+
+/// Appends a sequence of bits, packed eight to a byte.
+fn write_bits(output: &mut Vec<u8>, bits: impl Iterator<Item = bool>) {
+    let mut byte = 0;
+    let mut count = 0;
+
+    for bit in bits {
+        byte |= (bit as u8) << (count % 8);
+        count += 1;
+
+        if count % 8 == 0 {
+            output.push(std::mem::take(&mut byte));
+        }
+    }
+
+    if count % 8 != 0 {
+        output.push(byte);
+    }
+}
+
+/// Appends little-endian floating-point numbers to the output.
+fn write_f32s(output: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        output.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Appends an unsigned LEB128 integer to the output.
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while 0x80 <= value {
+        output.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+
+    output.push(value as u8);
+}
+
+/// Consumes data from the front of a byte slice.
+struct ByteReader<'a> {
+    /// The bytes that have not yet been read.
+    bytes: &'a [u8]
+}
+
+impl<'a> ByteReader<'a> {
+    /// The maximum number of bytes in an encoded `u64`.
+    const MAX_VARINT_LENGTH: u32 = 10;
+
+    /// Reads a single byte.
+    fn read_byte(&mut self) -> Result<u8, RecordingError> {
+        let (&first, rest) = self.bytes.split_first().ok_or(RecordingError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    /// Reads the given number of bytes.
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], RecordingError> {
+        if self.bytes.len() < length {
+            return Err(RecordingError::UnexpectedEnd);
+        }
+
+        let (result, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    /// Reads the given number of bits, packed eight to a byte.
+    fn read_bits(&mut self, count: usize) -> Result<Vec<bool>, RecordingError> {
+        let bytes = self.read_bytes(count.div_ceil(8))?;
+        Ok((0..count).map(|x| bytes[x / 8] & (1 << (x % 8)) != 0).collect())
+    }
+
+    /// Reads little-endian floating-point numbers.
+    fn read_f32s<const N: usize>(&mut self) -> Result<[f32; N], RecordingError> {
+        let mut result = [0.0; N];
+        for value in &mut result {
+            let bytes = self.read_bytes(4)?;
+            *value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        Ok(result)
+    }
+
+    /// Reads a little-endian `i32`.
+    fn read_i32(&mut self) -> Result<i32, RecordingError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a count of items. Since every item occupies at least one bit, counts
+    /// that could not fit in the remaining data are rejected before anything is allocated.
+    fn read_count(&mut self) -> Result<usize, RecordingError> {
+        let value = self.read_varint()?;
+        if (8 * self.bytes.len() as u64) < value {
+            return Err(RecordingError::UnexpectedEnd);
+        }
+
+        Ok(value as usize)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    fn read_varint(&mut self) -> Result<u64, RecordingError> {
+        let mut result = 0u64;
+
+        for index in 0..Self::MAX_VARINT_LENGTH {
+            let byte = self.read_byte()?;
+            let bits = (byte & 0x7F) as u64;
+            let shift = 7 * index;
+
+            if (bits << shift) >> shift != bits {
+                return Err(RecordingError::InvalidVarint);
+            }
+
+            result |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        Err(RecordingError::InvalidVarint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a frame whose values depend upon the index, with the given action counts.
+    fn frame(index: usize, analog_count: usize, digital_count: usize) -> InputFrame {
+        InputFrame {
+            analog: (0..analog_count).map(|x| [0.0, 0.25 * (index + x) as f32][(index + x) % 2]).collect(),
+            delta_time: 1.0 / 60.0,
+            digital: (0..digital_count).map(|x| DigitalSample { held: (index + x).is_multiple_of(3), pressed: (index + x).is_multiple_of(5) }).collect(),
+            pointer_delta: [index as f32, -0.5 * index as f32],
+            pointer_direction: index.is_multiple_of(2).then_some([0.0, 0.6, -0.8]),
+            scroll_delta: [index as i32 - 2, -(index as i32)]
+        }
+    }
+
+    /// Creates a recording with a start pose and two streams of different shapes.
+    fn recording() -> InputRecording {
+        InputRecording {
+            start: Some(StartPose {
+                position: [1.5, -2.0, 300.25],
+                rotation: [0.0, 0.6, 0.0, 0.8]
+            }),
+            streams: vec![
+                InputStream {
+                    analog_count: 4,
+                    digital_count: 39,
+                    frames: (0..5).map(|x| frame(x, 4, 39)).collect(),
+                    name: "Player controller".to_string()
+                },
+                InputStream {
+                    analog_count: 0,
+                    digital_count: 1,
+                    frames: (0..3).map(|x| frame(x, 0, 1)).collect(),
+                    name: "Ünïcode".to_string()
+                }
+            ]
+        }
+    }
+
+    #[test]
+    fn recordings_round_trip() {
+        let recording = recording();
+        assert_eq!(decode(&encode(&recording)), Ok(recording));
+    }
+
+    #[test]
+    fn empty_recordings_round_trip() {
+        let recording = InputRecording::default();
+        assert_eq!(decode(&encode(&recording)), Ok(recording));
+    }
+
+    #[test]
+    fn varints_round_trip() {
+        for value in [0, 1, 0x7F, 0x80, 0x3FFF, 0x4000, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value);
+            let mut reader = ByteReader { bytes: &bytes };
+            assert_eq!(reader.read_varint(), Ok(value));
+            assert!(reader.bytes.is_empty());
+        }
+    }
+
+    #[test]
+    fn overlong_varints_are_rejected() {
+        let mut reader = ByteReader { bytes: &[0xFF; 11] };
+        assert_eq!(reader.read_varint(), Err(RecordingError::InvalidVarint));
+
+        let mut reader = ByteReader { bytes: &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02] };
+        assert_eq!(reader.read_varint(), Err(RecordingError::InvalidVarint));
+    }
+
+    #[test]
+    fn truncated_recordings_are_rejected() {
+        let bytes = encode(&recording());
+        for length in 0..bytes.len() {
+            assert!(decode(&bytes[..length]).is_err(), "accepted a recording truncated to {length} bytes");
+        }
+    }
+
+    #[test]
+    fn trailing_data_is_rejected() {
+        let mut bytes = encode(&recording());
+        bytes.push(0);
+        assert_eq!(decode(&bytes), Err(RecordingError::TrailingData));
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        let bytes = encode(&recording());
+
+        let mut signature = bytes.clone();
+        signature[0] = b'X';
+        assert_eq!(decode(&signature), Err(RecordingError::InvalidSignature));
+
+        let mut version = bytes.clone();
+        version[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert_eq!(decode(&version), Err(RecordingError::UnsupportedVersion(VERSION + 1)));
+    }
+
+    #[test]
+    fn float_scroll_recordings_are_rounded() {
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.extend_from_slice(&FLOAT_SCROLL_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[0, 1, 0, 0, 0, 1]);
+        write_f32s(&mut bytes, &[0.5, 1.0, 2.0, 0.9, -2.2]);
+        bytes.push(0);
+
+        let recording = decode(&bytes).expect("Failed to decode recording");
+        assert_eq!(recording.streams[0].frames[0].scroll_delta, [1, -2]);
+        assert_eq!(recording.streams[0].frames[0].pointer_delta, [1.0, 2.0]);
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[0, 1, 2, 0xC3, 0x28, 0, 0, 0]);
+        assert_eq!(decode(&bytes), Err(RecordingError::InvalidName));
+    }
+
+    #[test]
+    fn oversized_counts_are_rejected() {
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.push(0);
+        write_varint(&mut bytes, u64::MAX);
+        assert_eq!(decode(&bytes), Err(RecordingError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn garbage_does_not_panic() {
+        let mut state = 0x2545F4914F6CDD1Du64;
+        for length in 0..2000 {
+            let mut bytes = SIGNATURE.to_vec();
+            bytes.extend_from_slice(&VERSION.to_le_bytes());
+            bytes.extend((0..length % 200).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            }));
+
+            let _ = decode(&bytes);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use crate::recording::*;
+use voxel_engine::*;
+use voxel_engine::input::*;
+use voxel_engine::math::*;
+use voxel_engine::player::*;
+use voxel_std_types::bindings::*;
+use voxel_std_types::recording::*;
+use voxel_std_types::targeting::*;
+use wings::*;
+
+/// Reads and writes the binary representation of recordings.
+mod encoding;
+/// Types for describing recorded play sessions.
+mod recording;
+
+instantiate_systems!(Client, [SessionRecorder]);
+
+/// Records the input that mods read each frame into a file, and replays a recorded file in place of the
+/// player's input so that a session is reproduced. Mods take part by passing each frame of their input
+/// through the `InputRecorder` system before using it, and by resetting any state that input does not reproduce
+/// when a new session begins. The world itself is not restored, so a session should be replayed in the world
+/// in which it was recorded. Since the player controller depends upon this system, the recorder's own action
+/// is provided through the `InputRecorder` system for the player controller to list in the key binding menu.
+#[export_system(InputRecorder)]
+pub struct SessionRecorder {
+    /// The recorder's actions, alongside their bindings, so that they may be listed for rebinding.
+    actions: Vec<BoundAction>,
+    /// The context handle.
+    ctx: WingsContextHandle<Self>,
+    /// Whether a session is being recorded or replayed.
+    mode: RecorderMode,
+    /// A message describing the result of the last operation.
+    notice: Option<String>,
+    /// Whether the recorder window is shown.
+    open: bool,
+    /// The file to which sessions are recorded, and from which they are replayed.
+    path: String,
+    /// Identifies the current recording or replay, incremented whenever one begins.
+    session: u32,
+    /// Holds handles for accessing user input.
+    user_actions: UserActions
+}
+
+impl SessionRecorder {
+    /// Starts, stops, or replays a recording as requested by the player.
+    fn apply(&mut self, command: RecorderCommand) {
+        match command {
+            RecorderCommand::Record => {
+                let transform = self.ctx.get::<dyn Player>().get_transform();
+                self.mode = RecorderMode::Recording(InputRecording {
+                    start: Some(StartPose {
+                        position: to_voxel_space(transform.position).to_array(),
+                        rotation: transform.rotation.to_array()
+                    }),
+                    streams: Vec::new()
+                });
+                self.notice = None;
+                self.session = self.session.wrapping_add(1);
+            },
+            RecorderCommand::Play => match self.load() {
+                Ok(recording) => {
+                    if let Some(start) = recording.start {
+                        let mut player = self.ctx.get_mut::<dyn Player>();
+                        let mut transform = player.get_transform();
+                        transform.position = Vec3A::from_array(start.position).into();
+                        transform.rotation = Quat::from_array(start.rotation);
+                        player.set_transform(transform);
+                    }
+
+                    self.notice = Some(format!("Replaying {} frames from {}", recording.frame_count(), self.path));
+                    self.mode = RecorderMode::Playing(Playback::new(recording));
+                    self.session = self.session.wrapping_add(1);
+                },
+                Err(error) => self.notice = Some(format!("Failed to load {}: {error}", self.path))
+            },
+            RecorderCommand::Stop => match std::mem::take(&mut self.mode) {
+                RecorderMode::Recording(recording) => match self.save(&recording) {
+                    Ok(()) => self.notice = Some(format!("Saved {} frames to {}", recording.frame_count(), self.path)),
+                    Err(error) => self.notice = Some(format!("Failed to save {}: {error}", self.path))
+                },
+                RecorderMode::Playing(_) => self.notice = Some("Stopped replaying".to_string()),
+                RecorderMode::Idle => {}
+            }
+        }
+    }
+
+    /// Writes a recording to the recording file.
+    fn save(&self, recording: &InputRecording) -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::path::Path::new(&self.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, recording.write())?;
+        Ok(())
+    }
+
+    /// Reads a recording from the recording file.
+    fn load(&self) -> Result<InputRecording, Box<dyn std::error::Error>> {
+        let bytes = std::fs::read(&self.path)?;
+        Ok(InputRecording::read(&bytes)?)
+    }
+
+    /// Draws the recorder window, if it is open.
+    /// Returns the command that the player requested, if any.
+    fn draw_gui(&mut self) -> Option<RecorderCommand> {
+        if !self.open {
+            return None;
+        }
+
+        let egui_system = self.ctx.get::<dyn egui::Egui>();
+        let egui_ctx = egui_system.context();
+
+        let mut command = None;
+        let mut open = self.open;
+        egui::Window::new("Input recorder").open(&mut open).resizable(false).show(&egui_ctx, |ui| {
+            let idle = matches!(self.mode, RecorderMode::Idle);
+
+            ui.horizontal(|ui| {
+                ui.label("File");
+                ui.add_enabled(idle, egui::TextEdit::singleline(&mut self.path));
+            });
+
+            ui.label(self.mode.describe());
+
+            ui.horizontal(|ui| {
+                if idle {
+                    if ui.button("Record").clicked() {
+                        command = Some(RecorderCommand::Record);
+                    }
+
+                    if ui.button("Replay").clicked() {
+                        command = Some(RecorderCommand::Play);
+                    }
+                }
+                else if ui.button("Stop").clicked() {
+                    command = Some(RecorderCommand::Stop);
+                }
+            });
+
+            if let Some(notice) = &self.notice {
+                ui.separator();
+                ui.label(notice);
+            }
+        });
+
+        self.open = open;
+        command
+    }
+
+    /// Shows or hides the recorder window, and applies any command from it.
+    fn handle_recorder_input(&mut self, _: &voxel_engine::timing::on::Frame) {
+        self.open ^= self.ctx.get::<dyn Input>().get(self.user_actions.toggle_recorder).pressed;
+
+        if let Some(command) = self.draw_gui() {
+            self.apply(command);
+        }
+    }
+
+    /// Registers the actions for opening the recorder, with the player's overrides applied.
+    /// Returns the actions, alongside their bindings so that conflicts may be reported and the actions listed for rebinding.
+    fn get_user_actions(ctx: &mut WingsContextHandle<Self>) -> (UserActions, ActionBindings) {
+        let mut input = ctx.get_mut::<dyn Input>();
+        let mut bindings = ActionBindings::new("Input recorder");
+
+        let toggle_recorder = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle input recorder",
+            "Shows or hides the window for recording and replaying sessions.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::F8)
+                },
+            ]
+        );
+
+        let actions = UserActions {
+            toggle_recorder
+        };
+
+        (actions, bindings)
+    }
+}
+
+impl InputRecorder for SessionRecorder {
+    fn process_frame(&mut self, source: String, frame: InputFrame) -> InputFrame {
+        match &mut self.mode {
+            RecorderMode::Idle => frame,
+            RecorderMode::Recording(recording) => {
+                recording.push(&source, frame.clone());
+                frame
+            },
+            RecorderMode::Playing(playback) => match playback.next(&source) {
+                Some(recorded) => recorded,
+                None => {
+                    if playback.is_finished() {
+                        self.mode = RecorderMode::Idle;
+                        self.notice = Some("Finished replaying".to_string());
+                    }
+
+                    frame
+                }
+            }
+        }
+    }
+
+    fn session(&self) -> u32 {
+        self.session
+    }
+
+    fn actions(&self) -> Vec<BoundAction> {
+        self.actions.clone()
+    }
+}
+
+impl WingsSystem for SessionRecorder {
+    const DEPENDENCIES: Dependencies = dependencies()
+        .with::<dyn egui::Egui>()
+        .with::<dyn Input>()
+        .with::<dyn Player>();
+
+    const EVENT_HANDLERS: EventHandlers<Self> = event_handlers()
+        .with(Self::handle_recorder_input);
+
+    fn new(mut ctx: WingsContextHandle<Self>) -> Self {
+        let (user_actions, bindings) = Self::get_user_actions(&mut ctx);
+
+        let actions = bindings.actions();
+        let mode = RecorderMode::Idle;
+        let notice = Some(bindings.warnings().join("\n")).filter(|x| !x.is_empty());
+        let open = false;
+        let path = "recordings/session.vxir".to_string();
+        let session = 0;
+
+        Self {
+            actions,
+            ctx,
+            mode,
+            notice,
+            open,
+            path,
+            session,
+            user_actions
+        }
+    }
+}
+
+/// An operation requested from the recorder window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RecorderCommand {
+    /// Begins recording a new session.
+    Record,
+    /// Replays the recording file.
+    Play,
+    /// Saves the session being recorded, or stops replaying.
+    Stop
+}
+
+/// Whether the recorder is capturing or replaying input.
+#[derive(Clone, Debug, Default)]
+enum RecorderMode {
+    /// Input passes through unchanged.
+    #[default]
+    Idle,
+    /// Input is appended to a recording.
+    Recording(InputRecording),
+    /// Input is replaced by the frames of a recording.
+    Playing(Playback)
+}
+
+impl RecorderMode {
+    /// Gets a short description of what the recorder is doing.
+    fn describe(&self) -> String {
+        match self {
+            Self::Idle => "Idle".to_string(),
+            Self::Recording(recording) => format!("Recording: {} frames", recording.frame_count()),
+            Self::Playing(playback) => format!("Replaying: frame {} of {}", playback.position(), playback.frame_count())
+        }
+    }
+}
+
+/// Holds the set of actions relevant to user input.
+#[derive(Copy, Clone, Debug)]
+struct UserActions {
+    /// Shows or hides the recorder window.
+    pub toggle_recorder: ActionId<Digital>
+}
@@ -0,0 +1,152 @@
+use crate::encoding::*;
+use std::fmt;
+use voxel_std_types::recording::*;
+
+/// A recorded play session, holding the frames of input that each mod read.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    /// The pose of the player when recording began, if it was known.
+    pub start: Option<StartPose>,
+    /// The frames read by each mod, in the order in which the mods first passed a frame to the recorder.
+    pub streams: Vec<InputStream>
+}
+
+impl InputRecording {
+    /// Loads a recording from its binary representation.
+    pub fn read(bytes: &[u8]) -> Result<Self, RecordingError> {
+        decode(bytes)
+    }
+
+    /// Produces the binary representation of this recording.
+    pub fn write(&self) -> Vec<u8> {
+        encode(self)
+    }
+
+    /// Appends a frame to the stream of the given mod, creating the stream if necessary.
+    pub fn push(&mut self, source: &str, frame: InputFrame) {
+        match self.streams.iter_mut().find(|x| x.name == source) {
+            Some(stream) => stream.push(frame),
+            None => self.streams.push(InputStream::new(source.to_string(), frame))
+        }
+    }
+
+    /// Gets the number of frames in the longest stream.
+    pub fn frame_count(&self) -> usize {
+        self.streams.iter().map(|x| x.frames.len()).max().unwrap_or_default()
+    }
+}
+
+/// The frames of input read by a single mod.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputStream {
+    /// The number of analog actions in every frame.
+    pub analog_count: usize,
+    /// The number of digital actions in every frame.
+    pub digital_count: usize,
+    /// The recorded frames, in order.
+    pub frames: Vec<InputFrame>,
+    /// The name that the mod passed to the recorder.
+    pub name: String
+}
+
+impl InputStream {
+    /// Creates a stream whose frames have the same number of actions as the first frame.
+    fn new(name: String, first: InputFrame) -> Self {
+        Self {
+            analog_count: first.analog.len(),
+            digital_count: first.digital.len(),
+            frames: vec![first],
+            name
+        }
+    }
+
+    /// Appends a frame, padding or truncating its actions to match the rest of the stream.
+    fn push(&mut self, mut frame: InputFrame) {
+        frame.analog.resize(self.analog_count, 0.0);
+        frame.digital.resize(self.digital_count, DigitalSample::default());
+        self.frames.push(frame);
+    }
+}
+
+/// The position and orientation of the player when a recording began.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StartPose {
+    /// The voxel-space position of the player.
+    pub position: [f32; 3],
+    /// The rotation of the player, as a quaternion.
+    pub rotation: [f32; 4]
+}
+
+/// Describes why a recording could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordingError {
+    /// The data does not begin with the recording file signature.
+    InvalidSignature,
+    /// A stream name was not valid UTF-8.
+    InvalidName,
+    /// A variable-length integer was longer than permitted.
+    InvalidVarint,
+    /// The data ended before the recording was complete.
+    UnexpectedEnd,
+    /// The data continued after the recording was complete.
+    TrailingData,
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u16)
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature => f.write_str("not an input recording"),
+            Self::InvalidName => f.write_str("stream name is not valid UTF-8"),
+            Self::InvalidVarint => f.write_str("malformed variable-length integer"),
+            Self::UnexpectedEnd => f.write_str("unexpected end of data"),
+            Self::TrailingData => f.write_str("unexpected data after the end of the recording"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported recording version {version}")
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+/// Steps through the streams of a recording as mods request frames.
+#[derive(Clone, Debug)]
+pub struct Playback {
+    /// The index of the next frame of each stream.
+    positions: Vec<usize>,
+    /// The recording being replayed.
+    recording: InputRecording
+}
+
+impl Playback {
+    /// Begins replaying a recording from its first frame.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            positions: vec![0; recording.streams.len()],
+            recording
+        }
+    }
+
+    /// Gets the next frame recorded for the given mod, if any remain.
+    pub fn next(&mut self, source: &str) -> Option<InputFrame> {
+        let index = self.recording.streams.iter().position(|x| x.name == source)?;
+        let frame = self.recording.streams[index].frames.get(self.positions[index])?.clone();
+        self.positions[index] += 1;
+        Some(frame)
+    }
+
+    /// Whether every stream has been replayed completely.
+    pub fn is_finished(&self) -> bool {
+        self.recording.streams.iter().zip(&self.positions).all(|(stream, &position)| stream.frames.len() <= position)
+    }
+
+    /// Gets the number of frames replayed from the longest stream.
+    pub fn position(&self) -> usize {
+        self.positions.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Gets the number of frames in the longest stream.
+    pub fn frame_count(&self) -> usize {
+        self.recording.frame_count()
+    }
+}
@@ -0,0 +1,333 @@
+use crate::*;
+
+/// The bytes at the start of every `.vox` file.
+const SIGNATURE: [u8; 4] = *b"VOX ";
+/// The versions of the format that may be read.
+const SUPPORTED_VERSIONS: [u32; 2] = [150, 200];
+
+/// Reads a `.vox` file from its binary representation. The file is a `MAIN` chunk whose
+/// children describe the models, palette, and scene graph. Every chunk is laid out as:
+///
+/// - a four-byte identifier
+/// - the length of the chunk content, as a little-endian `u32`
+/// - the length of the child chunks, as a little-endian `u32`
+/// - the chunk content, followed by the child chunks
+///
+/// Chunks with unknown identifiers are skipped.
+pub fn decode(bytes: &[u8]) -> Result<VoxFile, VoxError> {
+    let mut reader = ByteReader { bytes };
+
+    if reader.read_bytes(SIGNATURE.len())? != SIGNATURE {
+        return Err(VoxError::InvalidSignature);
+    }
+
+    let version = reader.read_u32()?;
+    if !SUPPORTED_VERSIONS.contains(&version) {
+        return Err(VoxError::UnsupportedVersion(version));
+    }
+
+    let main = reader.read_chunk()?;
+    let mut chunks = ByteReader { bytes: main.children };
+
+    let mut file = VoxFile {
+        hidden_layers: HashSet::new(),
+        models: Vec::new(),
+        nodes: HashMap::new(),
+        palette: default_palette()
+    };
+
+    let mut size = None;
+    while !chunks.bytes.is_empty() {
+        let chunk = chunks.read_chunk()?;
+        let mut content = ByteReader { bytes: chunk.content };
+
+        match &chunk.id {
+            b"SIZE" => size = Some([content.read_u32()?, content.read_u32()?, content.read_u32()?]),
+            b"XYZI" => file.models.push(read_model(&mut content, size.take().ok_or(VoxError::MissingSize)?)?),
+            b"RGBA" => for color in &mut file.palette[1..] {
+                *color = content.read_bytes(4)?.try_into().expect("Read the wrong number of bytes.");
+            },
+            b"LAYR" => {
+                let layer = content.read_u32()?;
+                if content.read_dict()?.get("_hidden").is_some_and(|x| x == "1") {
+                    file.hidden_layers.insert(layer);
+                }
+            },
+            b"nTRN" | b"nGRP" | b"nSHP" => {
+                let (node_id, node) = read_node(&chunk.id, &mut content)?;
+                file.nodes.insert(node_id, node);
+            },
+            _ => {}
+        }
+    }
+
+    Ok(file)
+}
+
+/// Reads the voxels of a model with the given size.
+fn read_model(content: &mut ByteReader, size: [u32; 3]) -> Result<VoxModel, VoxError> {
+    let count = content.read_u32()? as usize;
+    if content.bytes.len() / 4 < count {
+        return Err(VoxError::UnexpectedEnd);
+    }
+
+    let mut voxels = Vec::with_capacity(count);
+    for _ in 0..count {
+        let [x, y, z, color] = content.read_bytes(4)?.try_into().expect("Read the wrong number of bytes.");
+        if size[0] <= x as u32 || size[1] <= y as u32 || size[2] <= z as u32 {
+            return Err(VoxError::InvalidVoxel);
+        }
+
+        voxels.push(([x, y, z], color));
+    }
+
+    Ok(VoxModel { size, voxels })
+}
+
+/// Reads a node of the scene graph, returning its ID alongside the node.
+fn read_node(id: &[u8; 4], content: &mut ByteReader) -> Result<(u32, SceneNode), VoxError> {
+    let node_id = content.read_u32()?;
+    let attributes = content.read_dict()?;
+
+    let node = match id {
+        b"nTRN" => {
+            let child = content.read_u32()?;
+            let _reserved = content.read_u32()?;
+            let layer = content.read_u32()?;
+            let frame_count = content.read_u32()?;
+            let transform = if 0 < frame_count { Transform::from_frame(&content.read_dict()?)? } else { Transform::IDENTITY };
+
+            SceneNode::Transform {
+                child,
+                hidden: attributes.get("_hidden").is_some_and(|x| x == "1"),
+                layer: (layer != u32::MAX).then_some(layer),
+                transform
+            }
+        },
+        b"nGRP" => {
+            let count = content.read_u32()? as usize;
+            if content.bytes.len() / 4 < count {
+                return Err(VoxError::UnexpectedEnd);
+            }
+
+            SceneNode::Group {
+                children: (0..count).map(|_| content.read_u32()).collect::<Result<_, _>>()?
+            }
+        },
+        _ => {
+            let model_count = content.read_u32()?;
+            if model_count == 0 {
+                return Err(VoxError::InvalidNode(node_id));
+            }
+
+            SceneNode::Shape {
+                model: content.read_u32()?
+            }
+        }
+    };
+
+    Ok((node_id, node))
+}
+
+/// Gets the palette used by files that contain no `RGBA` chunk, matching MagicaVoxel's default.
+/// The palette begins with a six-level color cube ordered from white to dark blue, in which blue
+/// changes fastest and red slowest, followed by ten-level ramps of red, green, blue, and gray.
+fn default_palette() -> [[u8; 4]; 256] {
+    /// The intensities of each ramp, from brightest to darkest.
+    const RAMP: [u8; 10] = [0xEE, 0xDD, 0xBB, 0xAA, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+
+    let cube = (0..215).map(|index: u8| {
+        let [r, g, b] = [index / 36, index / 6 % 6, index % 6].map(|x| 0xFF - 0x33 * x);
+        [r, g, b, 255]
+    });
+
+    let ramps = [[1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 1, 1]].into_iter()
+        .flat_map(|mask| RAMP.map(|x| [x * mask[0], x * mask[1], x * mask[2], 255]));
+
+    let mut result = [[0; 4]; 256];
+    for (color, entry) in cube.chain(ramps).zip(&mut result[1..]) {
+        *entry = color;
+    }
+
+    result
+}
+
+/// A single chunk of a `.vox` file.
+struct Chunk<'a> {
+    /// The child chunks, which are stored consecutively.
+    children: &'a [u8],
+    /// The content of the chunk.
+    content: &'a [u8],
+    /// The identifier describing the type of the chunk.
+    id: [u8; 4]
+}
+
+/// Consumes data from the front of a byte slice.
+struct ByteReader<'a> {
+    /// The bytes that have not yet been read.
+    bytes: &'a [u8]
+}
+
+impl<'a> ByteReader<'a> {
+    /// Reads the given number of bytes.
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], VoxError> {
+        if self.bytes.len() < length {
+            return Err(VoxError::UnexpectedEnd);
+        }
+
+        let (result, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    /// Reads a little-endian `u32`.
+    fn read_u32(&mut self) -> Result<u32, VoxError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().expect("Read the wrong number of bytes.")))
+    }
+
+    /// Reads a chunk.
+    fn read_chunk(&mut self) -> Result<Chunk<'a>, VoxError> {
+        let id = self.read_bytes(4)?.try_into().expect("Read the wrong number of bytes.");
+        let content_length = self.read_u32()? as usize;
+        let children_length = self.read_u32()? as usize;
+
+        Ok(Chunk {
+            content: self.read_bytes(content_length)?,
+            children: self.read_bytes(children_length)?,
+            id
+        })
+    }
+
+    /// Reads a string prefixed by its length.
+    fn read_string(&mut self) -> Result<String, VoxError> {
+        let length = self.read_u32()? as usize;
+        let bytes = self.read_bytes(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| VoxError::InvalidString)
+    }
+
+    /// Reads a dictionary of string keys and values, prefixed by the number of entries.
+    fn read_dict(&mut self) -> Result<HashMap<String, String>, VoxError> {
+        let count = self.read_u32()?;
+        let mut result = HashMap::new();
+
+        for _ in 0..count {
+            let key = self.read_string()?;
+            let value = self.read_string()?;
+            result.insert(key, value);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Appends a chunk with the given content and no children.
+    fn push_chunk(output: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+        output.extend_from_slice(id);
+        output.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        output.extend_from_slice(&0u32.to_le_bytes());
+        output.extend_from_slice(content);
+    }
+
+    /// Builds a file whose `MAIN` chunk holds the given children.
+    fn file(children: &[u8]) -> Vec<u8> {
+        let mut result = b"VOX ".to_vec();
+        result.extend_from_slice(&150u32.to_le_bytes());
+        result.extend_from_slice(b"MAIN");
+        result.extend_from_slice(&0u32.to_le_bytes());
+        result.extend_from_slice(&(children.len() as u32).to_le_bytes());
+        result.extend_from_slice(children);
+        result
+    }
+
+    /// Encodes a sequence of little-endian `u32`s.
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    /// Builds the chunks of a single model with the given size and voxels.
+    fn model(size: [u32; 3], voxels: &[[u8; 4]]) -> Vec<u8> {
+        let mut result = Vec::new();
+        push_chunk(&mut result, b"SIZE", &words(&size));
+        let mut content = words(&[voxels.len() as u32]);
+        content.extend(voxels.iter().flatten());
+        push_chunk(&mut result, b"XYZI", &content);
+        result
+    }
+
+    #[test]
+    fn default_palette_matches_reference() {
+        let palette = default_palette();
+        assert_eq!(palette[0], [0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(palette[1], [0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(palette[2], [0xFF, 0xFF, 0xCC, 0xFF]);
+        assert_eq!(palette[7], [0xFF, 0xCC, 0xFF, 0xFF]);
+        assert_eq!(palette[37], [0xCC, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(palette[215], [0x00, 0x00, 0x33, 0xFF]);
+        assert_eq!(palette[216], [0xEE, 0x00, 0x00, 0xFF]);
+        assert_eq!(palette[225], [0x11, 0x00, 0x00, 0xFF]);
+        assert_eq!(palette[226], [0x00, 0xEE, 0x00, 0xFF]);
+        assert_eq!(palette[236], [0x00, 0x00, 0xEE, 0xFF]);
+        assert_eq!(palette[246], [0xEE, 0xEE, 0xEE, 0xFF]);
+        assert_eq!(palette[255], [0x11, 0x11, 0x11, 0xFF]);
+    }
+
+    #[test]
+    fn reads_models_and_palette() {
+        let mut children = model([2, 3, 4], &[[0, 0, 0, 1], [1, 2, 3, 200]]);
+        push_chunk(&mut children, b"RGBA", &(0..256 * 4).map(|x| x as u8).collect::<Vec<_>>());
+        push_chunk(&mut children, b"ABCD", b"ignored");
+
+        let file = VoxFile::read(&file(&children)).unwrap();
+        assert_eq!(file.models(), &[VoxModel { size: [2, 3, 4], voxels: vec![([0, 0, 0], 1), ([1, 2, 3], 200)] }]);
+        assert_eq!(file.color(0), [0; 4]);
+        assert_eq!(file.color(1), [0, 1, 2, 3]);
+        assert_eq!(file.color(255), [0xF8, 0xF9, 0xFA, 0xFB]);
+    }
+
+    #[test]
+    fn files_without_palette_use_default() {
+        let file = VoxFile::read(&file(&model([1, 1, 1], &[[0, 0, 0, 216]]))).unwrap();
+        assert_eq!(file.color(216), [0xEE, 0x00, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn voxel_outside_model_is_rejected() {
+        assert_eq!(VoxFile::read(&file(&model([2, 2, 2], &[[0, 2, 0, 1]]))).unwrap_err(), VoxError::InvalidVoxel);
+    }
+
+    #[test]
+    fn voxels_without_size_are_rejected() {
+        let mut children = Vec::new();
+        push_chunk(&mut children, b"XYZI", &words(&[0]));
+        assert_eq!(VoxFile::read(&file(&children)).unwrap_err(), VoxError::MissingSize);
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        assert_eq!(VoxFile::read(b"NOPE").unwrap_err(), VoxError::InvalidSignature);
+
+        let mut bytes = file(&[]);
+        bytes[4..8].copy_from_slice(&7u32.to_le_bytes());
+        assert_eq!(VoxFile::read(&bytes).unwrap_err(), VoxError::UnsupportedVersion(7));
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        let bytes = file(&model([2, 2, 2], &[[1, 1, 1, 5]]));
+        for length in 0..bytes.len() {
+            assert!(VoxFile::read(&bytes[..length]).is_err(), "Truncation to {length} bytes was accepted.");
+        }
+    }
+
+    #[test]
+    fn oversized_voxel_count_is_rejected() {
+        let mut children = Vec::new();
+        push_chunk(&mut children, b"SIZE", &words(&[1, 1, 1]));
+        push_chunk(&mut children, b"XYZI", &words(&[u32::MAX]));
+        assert_eq!(VoxFile::read(&file(&children)).unwrap_err(), VoxError::UnexpectedEnd);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::chunk::*;
+use crate::scene::*;
+use std::collections::*;
+use std::fmt;
+
+/// Reads the chunks of a `.vox` file.
+mod chunk;
+/// Resolves the scene graph that positions models relative to one another.
+mod scene;
+
+/// The contents of a MagicaVoxel `.vox` file.
+#[derive(Clone, Debug)]
+pub struct VoxFile {
+    /// The IDs of layers that are hidden in the editor.
+    hidden_layers: HashSet<u32>,
+    /// The models stored in the file, in the order that they appear.
+    models: Vec<VoxModel>,
+    /// The nodes of the scene graph, by ID.
+    nodes: HashMap<u32, SceneNode>,
+    /// The color of each palette index. Index zero is unused, since voxels refer to colors `1` through `255`.
+    palette: [[u8; 4]; 256]
+}
+
+impl VoxFile {
+    /// The largest number of voxels that a scene may contain once every model instance has been placed.
+    pub const MAX_SCENE_VOXELS: u64 = 1 << 22;
+
+    /// Loads a file from its binary representation.
+    pub fn read(bytes: &[u8]) -> Result<Self, VoxError> {
+        decode(bytes)
+    }
+
+    /// Gets the RGBA color of the given palette index.
+    pub fn color(&self, index: u8) -> [u8; 4] {
+        self.palette[index as usize]
+    }
+
+    /// Gets the models stored in the file, in the order that they appear.
+    pub fn models(&self) -> &[VoxModel] {
+        &self.models
+    }
+
+    /// Gets every visible voxel of the scene alongside its palette index, with the transforms
+    /// of the scene graph applied. Positions use MagicaVoxel's coordinate frame, where `z` points up.
+    /// Files without a scene graph place each model at the origin.
+    pub fn scene_voxels(&self) -> Result<Vec<([i32; 3], u8)>, VoxError> {
+        let instances = if self.nodes.is_empty() {
+            (0..self.models.len()).map(|x| (x, Transform::IDENTITY)).collect()
+        }
+        else {
+            instances(&self.nodes, &self.hidden_layers)?
+        };
+
+        let mut volume = 0;
+        for &(model, _) in &instances {
+            let voxels = self.models.get(model).ok_or(VoxError::InvalidModel(model as u32))?.voxels.len() as u64;
+            volume += voxels;
+            if Self::MAX_SCENE_VOXELS < volume {
+                return Err(VoxError::TooLarge(volume));
+            }
+        }
+
+        let mut result = Vec::with_capacity(volume as usize);
+        for (model, transform) in instances {
+            let model = &self.models[model];
+            let center = model.size.map(|x| (x / 2) as i32);
+
+            for &(position, color) in &model.voxels {
+                let centered = std::array::from_fn(|axis| position[axis] as i32 - center[axis]);
+                result.push((transform.apply(centered), color));
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// A single box of voxels stored in a `.vox` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoxModel {
+    /// The number of voxels along each axis.
+    pub size: [u32; 3],
+    /// The position and palette index of each filled voxel.
+    pub voxels: Vec<([u8; 3], u8)>
+}
+
+/// Describes why a `.vox` file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VoxError {
+    /// The data does not begin with the `.vox` file signature.
+    InvalidSignature,
+    /// The scene graph referenced a model that does not exist.
+    InvalidModel(u32),
+    /// The scene graph referenced a node that does not exist, or contains a cycle.
+    InvalidNode(u32),
+    /// A transform node contained a rotation that is not a valid signed permutation.
+    InvalidRotation(u8),
+    /// A string was not valid UTF-8.
+    InvalidString,
+    /// A voxel lay outside of the bounds of its model.
+    InvalidVoxel,
+    /// A chunk of voxel data appeared without a preceding size chunk.
+    MissingSize,
+    /// The data ended before the file was complete.
+    UnexpectedEnd,
+    /// The scene contains more voxels than permitted.
+    TooLarge(u64),
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u32)
+}
+
+impl fmt::Display for VoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature => f.write_str("not a MagicaVoxel file"),
+            Self::InvalidModel(model) => write!(f, "model {model} does not exist"),
+            Self::InvalidNode(node) => write!(f, "scene node {node} does not exist or forms a cycle"),
+            Self::InvalidRotation(rotation) => write!(f, "invalid rotation {rotation:#04x}"),
+            Self::InvalidString => f.write_str("string is not valid UTF-8"),
+            Self::InvalidVoxel => f.write_str("voxel lies outside of its model"),
+            Self::MissingSize => f.write_str("voxel data appeared without a model size"),
+            Self::UnexpectedEnd => f.write_str("unexpected end of data"),
+            Self::TooLarge(volume) => write!(f, "scene contains {volume} voxels, exceeding the maximum of {}", VoxFile::MAX_SCENE_VOXELS),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported file version {version}")
+        }
+    }
+}
+
+impl std::error::Error for VoxError {}
@@ -0,0 +1,265 @@
+use crate::*;
+
+/// A node of the scene graph, which positions instances of models.
+#[derive(Clone, Debug)]
+pub enum SceneNode {
+    /// Places its children into a single group.
+    Group {
+        /// The IDs of the child nodes.
+        children: Vec<u32>
+    },
+    /// Displays a model.
+    Shape {
+        /// The index of the displayed model.
+        model: u32
+    },
+    /// Moves and rotates its child.
+    Transform {
+        /// The ID of the child node.
+        child: u32,
+        /// Whether the child is hidden in the editor.
+        hidden: bool,
+        /// The layer to which the child belongs, if any.
+        layer: Option<u32>,
+        /// The transform applied to the child.
+        transform: Transform
+    }
+}
+
+/// A rotation by a signed permutation matrix, followed by an integer translation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Transform {
+    /// The rows of the rotation matrix.
+    rotation: [[i32; 3]; 3],
+    /// The translation applied after rotation.
+    translation: [i32; 3]
+}
+
+impl Transform {
+    /// The transform that leaves points unchanged.
+    pub const IDENTITY: Self = Self {
+        rotation: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+        translation: [0; 3]
+    };
+
+    /// Reads a transform from the attributes of an animation frame. The `_r` attribute packs
+    /// the rotation into a byte: bits 0-1 and 2-3 hold the column of the nonzero entry in the
+    /// first and second rows, while bits 4-6 hold the sign of each row. The `_t` attribute
+    /// holds the translation as three space-separated integers.
+    pub fn from_frame(attributes: &HashMap<String, String>) -> Result<Self, VoxError> {
+        let mut result = Self::IDENTITY;
+
+        if let Some(packed) = attributes.get("_r") {
+            let packed = packed.parse::<u8>().map_err(|_| VoxError::InvalidRotation(0))?;
+            let first = (packed & 3) as usize;
+            let second = ((packed >> 2) & 3) as usize;
+
+            if 2 < first || 2 < second || first == second {
+                return Err(VoxError::InvalidRotation(packed));
+            }
+
+            let columns = [first, second, 3 - first - second];
+            result.rotation = std::array::from_fn(|row| {
+                let mut entries = [0; 3];
+                entries[columns[row]] = [1, -1][((packed >> (4 + row)) & 1) as usize];
+                entries
+            });
+        }
+
+        if let Some(translation) = attributes.get("_t") {
+            for (axis, value) in result.translation.iter_mut().zip(translation.split_whitespace()) {
+                *axis = value.parse().unwrap_or_default();
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Transforms a point. Coordinates wrap rather than overflow, since translations are read from untrusted data.
+    pub fn apply(&self, point: [i32; 3]) -> [i32; 3] {
+        std::array::from_fn(|row| (0..3).fold(self.translation[row], |sum, column| sum.wrapping_add(self.rotation[row][column].wrapping_mul(point[column]))))
+    }
+
+    /// Gets the transform that applies `child` and then this transform.
+    pub fn then(&self, child: &Self) -> Self {
+        Self {
+            rotation: std::array::from_fn(|row| std::array::from_fn(|column| (0..3).map(|x| self.rotation[row][x] * child.rotation[x][column]).sum())),
+            translation: self.apply(child.translation)
+        }
+    }
+}
+
+/// Gets the index and accumulated transform of every visible model instance, starting from the root node.
+/// The graph is walked with an explicit stack, so deeply nested files cannot exhaust the call stack. Nodes may
+/// be shared between several parents, so the total number of visits is limited as well.
+pub fn instances(nodes: &HashMap<u32, SceneNode>, hidden_layers: &HashSet<u32>) -> Result<Vec<(usize, Transform)>, VoxError> {
+    /// The ID of the root node of every scene graph.
+    const ROOT_NODE: u32 = 0;
+
+    let mut path = HashSet::new();
+    let mut result = Vec::new();
+    let mut stack = vec![TraversalStep::Enter(ROOT_NODE, Transform::IDENTITY)];
+    let mut visits = 0;
+
+    while let Some(step) = stack.pop() {
+        let (node_id, parent) = match step {
+            TraversalStep::Enter(node_id, parent) => (node_id, parent),
+            TraversalStep::Exit(node_id) => {
+                path.remove(&node_id);
+                continue;
+            }
+        };
+
+        visits += 1;
+        if VoxFile::MAX_SCENE_VOXELS < visits {
+            return Err(VoxError::TooLarge(visits));
+        }
+
+        if !path.insert(node_id) {
+            return Err(VoxError::InvalidNode(node_id));
+        }
+
+        stack.push(TraversalStep::Exit(node_id));
+        match nodes.get(&node_id).ok_or(VoxError::InvalidNode(node_id))? {
+            SceneNode::Group { children } => stack.extend(children.iter().rev().map(|&x| TraversalStep::Enter(x, parent))),
+            SceneNode::Shape { model } => result.push((*model as usize, parent)),
+            SceneNode::Transform { child, hidden, layer, transform } => {
+                if !hidden && !layer.is_some_and(|x| hidden_layers.contains(&x)) {
+                    stack.push(TraversalStep::Enter(*child, parent.then(transform)));
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// A pending step of the depth-first walk through the scene graph.
+#[derive(Copy, Clone, Debug)]
+enum TraversalStep {
+    /// Visits a node beneath a parent with the given accumulated transform.
+    Enter(u32, Transform),
+    /// Leaves a node once all of its descendants have been visited, removing it from the current path.
+    Exit(u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a transform node from the given frame attributes.
+    fn transform_node(child: u32, attributes: &[(&str, &str)]) -> SceneNode {
+        SceneNode::Transform {
+            child,
+            hidden: false,
+            layer: None,
+            transform: Transform::from_frame(&attributes.iter().map(|&(key, value)| (key.to_string(), value.to_string())).collect()).unwrap()
+        }
+    }
+
+    #[test]
+    fn frame_without_attributes_is_identity() {
+        assert_eq!(Transform::from_frame(&HashMap::new()), Ok(Transform::IDENTITY));
+        assert_eq!(Transform::IDENTITY.apply([3, -4, 5]), [3, -4, 5]);
+    }
+
+    #[test]
+    fn frame_rotation_and_translation_are_applied() {
+        let attributes = HashMap::from([("_r".to_string(), "17".to_string()), ("_t".to_string(), "10 -20 30".to_string())]);
+        let transform = Transform::from_frame(&attributes).unwrap();
+        assert_eq!(transform.apply([1, 0, 0]), [10, -19, 30]);
+        assert_eq!(transform.apply([0, 1, 0]), [9, -20, 30]);
+        assert_eq!(transform.apply([0, 0, 1]), [10, -20, 31]);
+    }
+
+    #[test]
+    fn packed_identity_rotation_is_identity() {
+        let attributes = HashMap::from([("_r".to_string(), "4".to_string())]);
+        assert_eq!(Transform::from_frame(&attributes), Ok(Transform::IDENTITY));
+    }
+
+    #[test]
+    fn invalid_rotations_are_rejected() {
+        for (packed, error) in [("0", 0), ("3", 3), ("15", 15), ("x", 0)] {
+            let attributes = HashMap::from([("_r".to_string(), packed.to_string())]);
+            assert_eq!(Transform::from_frame(&attributes), Err(VoxError::InvalidRotation(error)));
+        }
+    }
+
+    #[test]
+    fn composed_transforms_apply_child_first() {
+        let rotate = Transform::from_frame(&HashMap::from([("_r".to_string(), "17".to_string())])).unwrap();
+        let translate = Transform::from_frame(&HashMap::from([("_t".to_string(), "1 2 3".to_string())])).unwrap();
+
+        assert_eq!(rotate.then(&translate).apply([1, 0, 0]), rotate.apply(translate.apply([1, 0, 0])));
+        assert_eq!(rotate.then(&translate).apply([1, 0, 0]), [-2, 2, 3]);
+        assert_eq!(translate.then(&rotate).apply([1, 0, 0]), [1, 3, 3]);
+    }
+
+    #[test]
+    fn instances_accumulate_transforms() {
+        let nodes = HashMap::from([
+            (0, transform_node(1, &[("_t", "5 0 0")])),
+            (1, SceneNode::Group { children: vec![2, 4] }),
+            (2, transform_node(3, &[("_t", "0 7 0")])),
+            (3, SceneNode::Shape { model: 0 }),
+            (4, SceneNode::Shape { model: 1 })
+        ]);
+
+        let result = instances(&nodes, &HashSet::new()).unwrap();
+        assert_eq!(result.iter().map(|&(model, transform)| (model, transform.apply([0; 3]))).collect::<Vec<_>>(), [(0, [5, 7, 0]), (1, [5, 0, 0])]);
+    }
+
+    #[test]
+    fn hidden_layers_are_skipped() {
+        let nodes = HashMap::from([
+            (0, SceneNode::Group { children: vec![1, 2] }),
+            (1, SceneNode::Transform { child: 3, hidden: false, layer: Some(1), transform: Transform::IDENTITY }),
+            (2, SceneNode::Transform { child: 3, hidden: true, layer: None, transform: Transform::IDENTITY }),
+            (3, SceneNode::Shape { model: 0 })
+        ]);
+
+        assert_eq!(instances(&nodes, &HashSet::from([1])), Ok(Vec::new()));
+        assert_eq!(instances(&nodes, &HashSet::new()).map(|x| x.len()), Ok(1));
+    }
+
+    #[test]
+    fn shared_nodes_are_not_cycles() {
+        let nodes = HashMap::from([
+            (0, SceneNode::Group { children: vec![1, 1] }),
+            (1, SceneNode::Shape { model: 0 })
+        ]);
+
+        assert_eq!(instances(&nodes, &HashSet::new()).map(|x| x.len()), Ok(2));
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let nodes = HashMap::from([
+            (0, SceneNode::Group { children: vec![1] }),
+            (1, transform_node(0, &[]))
+        ]);
+
+        assert_eq!(instances(&nodes, &HashSet::new()), Err(VoxError::InvalidNode(0)));
+    }
+
+    #[test]
+    fn missing_nodes_are_rejected() {
+        let nodes = HashMap::from([(0, SceneNode::Group { children: vec![9] })]);
+        assert_eq!(instances(&nodes, &HashSet::new()), Err(VoxError::InvalidNode(9)));
+    }
+
+    #[test]
+    fn deep_graphs_do_not_exhaust_stack() {
+        /// The number of nested transform nodes.
+        const DEPTH: u32 = 100_000;
+
+        let mut nodes = (0..DEPTH).map(|x| (x, transform_node(x + 1, &[("_t", "1 0 0")]))).collect::<HashMap<_, _>>();
+        nodes.insert(DEPTH, SceneNode::Shape { model: 0 });
+
+        let result = instances(&nodes, &HashSet::new()).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].1.apply([0; 3]), [DEPTH as i32, 0, 0]);
+    }
+
+}
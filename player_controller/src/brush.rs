@@ -0,0 +1,192 @@
+use voxel_engine::math::*;
+
+/// Determines the set of voxels affected by a single placement or deletion.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Brush {
+    /// Whether only the surface of the shape should be affected.
+    pub hollow: bool,
+    /// The radius of the brush. A radius of zero affects a single voxel.
+    pub radius: u32,
+    /// The shape of the brush.
+    pub shape: BrushShape
+}
+
+impl Brush {
+    /// The largest radius that the brush may have.
+    pub const MAX_RADIUS: u32 = 8;
+
+    /// Changes the brush radius by the given amount, clamping it to the allowed range.
+    pub fn adjust_radius(&mut self, delta: i32) {
+        self.radius = (self.radius as i64 + delta as i64).clamp(0, Self::MAX_RADIUS as i64) as u32;
+    }
+
+    /// Gets a short description of the brush for display.
+    pub fn describe(&self) -> String {
+        if self.radius == 0 {
+            "Single voxel".to_string()
+        }
+        else {
+            let fill = ["solid", "hollow"][self.hollow as usize];
+            format!("{} brush, radius {}, {fill}", self.shape.name(), self.radius)
+        }
+    }
+
+    /// Gets the minimum and maximum corners of the box that bounds the brush around the given voxel.
+    pub fn bounds(&self, center: IVec3) -> (IVec3, IVec3) {
+        let radius = IVec3::splat(self.radius as i32);
+        (center - radius, center + radius + IVec3::ONE)
+    }
+
+    /// Gets all voxels affected by the brush when centered at the given voxel.
+    pub fn voxels(&self, center: IVec3) -> Vec<IVec3> {
+        let radius = self.radius as i32;
+        let mut result = Vec::new();
+
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    let offset = ivec3(x, y, z);
+                    if self.contains(offset) && (!self.hollow || self.on_surface(offset)) {
+                        result.push(center + offset);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Determines whether the given offset from the brush center lies within the brush shape.
+    fn contains(&self, offset: IVec3) -> bool {
+        let radius = self.radius as f32 + 0.5;
+        match self.shape {
+            BrushShape::Cube => offset.abs().max_element() <= self.radius as i32,
+            BrushShape::Cylinder => offset.xz().as_vec2().length() <= radius && offset.y.unsigned_abs() <= self.radius,
+            BrushShape::Sphere => offset.as_vec3a().length() <= radius
+        }
+    }
+
+    /// Determines whether the given offset has a face neighbor that lies outside of the brush shape.
+    fn on_surface(&self, offset: IVec3) -> bool {
+        [IVec3::X, IVec3::NEG_X, IVec3::Y, IVec3::NEG_Y, IVec3::Z, IVec3::NEG_Z]
+            .into_iter()
+            .any(|x| !self.contains(offset + x))
+    }
+}
+
+/// The shapes that a brush may take.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BrushShape {
+    /// An axis-aligned cube.
+    #[default]
+    Cube,
+    /// An upright cylinder, as tall as it is wide.
+    Cylinder,
+    /// A sphere.
+    Sphere
+}
+
+impl BrushShape {
+    /// Gets the human-readable name of the shape.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cube => "Cube",
+            Self::Cylinder => "Cylinder",
+            Self::Sphere => "Sphere"
+        }
+    }
+
+    /// Gets the shape that follows this one when cycling through shapes.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Cube => Self::Cylinder,
+            Self::Cylinder => Self::Sphere,
+            Self::Sphere => Self::Cube
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a solid brush with the given shape and radius.
+    fn brush(shape: BrushShape, radius: u32) -> Brush {
+        Brush {
+            hollow: false,
+            radius,
+            shape
+        }
+    }
+
+    #[test]
+    fn zero_radius_affects_a_single_voxel() {
+        for shape in [BrushShape::Cube, BrushShape::Cylinder, BrushShape::Sphere] {
+            assert_eq!(brush(shape, 0).voxels(ivec3(3, -2, 5)), vec![ivec3(3, -2, 5)]);
+        }
+    }
+
+    #[test]
+    fn cube_fills_its_bounds() {
+        let brush = brush(BrushShape::Cube, 2);
+        let voxels = brush.voxels(IVec3::ONE);
+        let (min, max) = brush.bounds(IVec3::ONE);
+
+        assert_eq!(voxels.len(), 125);
+        assert!(voxels.iter().all(|x| x.cmpge(min).all() && x.cmplt(max).all()));
+        assert!(voxels.contains(&ivec3(3, 3, 3)));
+        assert!(voxels.contains(&ivec3(-1, -1, -1)));
+    }
+
+    #[test]
+    fn cylinder_rounds_its_corners_horizontally() {
+        let voxels = brush(BrushShape::Cylinder, 2).voxels(IVec3::ZERO);
+
+        assert_eq!(voxels.len(), 105);
+        assert!(voxels.contains(&ivec3(2, 2, 0)));
+        assert!(voxels.contains(&ivec3(1, -2, 1)));
+        assert!(!voxels.contains(&ivec3(2, 0, 2)));
+    }
+
+    #[test]
+    fn sphere_rounds_its_corners_in_every_direction() {
+        let voxels = brush(BrushShape::Sphere, 2).voxels(IVec3::ZERO);
+
+        assert_eq!(voxels.len(), 81);
+        assert!(voxels.contains(&ivec3(0, -2, 0)));
+        assert!(voxels.contains(&ivec3(1, 1, 1)));
+        assert!(!voxels.contains(&ivec3(2, 2, 0)));
+    }
+
+    #[test]
+    fn hollow_brushes_omit_their_interior() {
+        for shape in [BrushShape::Cube, BrushShape::Cylinder, BrushShape::Sphere] {
+            let solid = brush(shape, 2);
+            let hollow = Brush { hollow: true, ..solid };
+            let surface = hollow.voxels(IVec3::ZERO);
+
+            assert!(!surface.contains(&IVec3::ZERO));
+            assert!(surface.iter().all(|x| solid.voxels(IVec3::ZERO).contains(x)));
+            assert!(surface.contains(&ivec3(0, 2, 0)));
+        }
+
+        let hollow_cube = Brush { hollow: true, ..brush(BrushShape::Cube, 1) };
+        assert_eq!(hollow_cube.voxels(IVec3::ZERO).len(), 26);
+    }
+
+    #[test]
+    fn radius_is_clamped_to_the_allowed_range() {
+        let mut brush = Brush::default();
+        brush.adjust_radius(-1);
+        assert_eq!(brush.radius, 0);
+
+        brush.adjust_radius(3);
+        assert_eq!(brush.radius, 3);
+
+        brush.adjust_radius(i32::MAX);
+        assert_eq!(brush.radius, Brush::MAX_RADIUS);
+
+        brush.adjust_radius(i32::MIN);
+        assert_eq!(brush.radius, 0);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::*;
+use voxel_engine::math::*;
+
+/// Finds groups of connected voxels that share a property, such as their material.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FloodFill {
+    /// Which neighbors of a voxel are considered connected to it.
+    pub connectivity: Connectivity
+}
+
+impl FloodFill {
+    /// The largest number of voxels that a single flood fill may affect.
+    pub const MAX_VOXELS: usize = 4096;
+
+    /// Gets a short description of the flood fill for display.
+    pub fn describe(&self) -> String {
+        format!("Flood fill: {} connectivity", self.connectivity.name())
+    }
+
+    /// Gets every voxel connected to the start voxel through voxels that satisfy the predicate,
+    /// including the start voxel itself. Returns `None` if more than [`Self::MAX_VOXELS`] voxels are connected.
+    pub fn connected(&self, start: IVec3, mut predicate: impl FnMut(IVec3) -> bool) -> Option<Vec<IVec3>> {
+        let offsets = self.connectivity.offsets();
+        let mut queue = VecDeque::from([start]);
+        let mut visited = HashSet::from([start]);
+        let mut result = Vec::new();
+
+        while let Some(voxel) = queue.pop_front() {
+            result.push(voxel);
+            if Self::MAX_VOXELS < result.len() {
+                return None;
+            }
+
+            for &offset in &offsets {
+                let neighbor = voxel + offset;
+                if visited.insert(neighbor) && predicate(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// Determines which neighboring voxels are connected.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Voxels are connected through their six faces.
+    #[default]
+    Face,
+    /// Voxels are connected through their faces and twelve edges.
+    Edge,
+    /// Voxels are connected through their faces, edges, and eight corners.
+    Corner
+}
+
+impl Connectivity {
+    /// Gets the human-readable name of the connectivity.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Face => "face",
+            Self::Edge => "edge",
+            Self::Corner => "corner"
+        }
+    }
+
+    /// Gets the connectivity that follows this one when cycling.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Face => Self::Edge,
+            Self::Edge => Self::Corner,
+            Self::Corner => Self::Face
+        }
+    }
+
+    /// Gets the offsets of every neighbor connected to a voxel.
+    fn offsets(self) -> Vec<IVec3> {
+        let max_nonzero = match self {
+            Self::Face => 1,
+            Self::Edge => 2,
+            Self::Corner => 3
+        };
+
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| ivec3(x, y, z))))
+            .filter(|x| (1..=max_nonzero).contains(&x.abs().element_sum()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connectivities_reach_their_neighbors() {
+        assert_eq!(Connectivity::Face.offsets().len(), 6);
+        assert_eq!(Connectivity::Edge.offsets().len(), 18);
+        assert_eq!(Connectivity::Corner.offsets().len(), 26);
+    }
+
+    #[test]
+    fn fills_follow_the_predicate_through_the_chosen_neighbors() {
+        let diagonal = [IVec3::ZERO, ivec3(1, 1, 0), ivec3(2, 2, 1)];
+        let mut fill = FloodFill::default();
+        assert_eq!(fill.connected(IVec3::ZERO, |x| diagonal.contains(&x)).map(|x| x.len()), Some(1));
+
+        fill.connectivity = Connectivity::Edge;
+        assert_eq!(fill.connected(IVec3::ZERO, |x| diagonal.contains(&x)).map(|x| x.len()), Some(2));
+
+        fill.connectivity = Connectivity::Corner;
+        assert_eq!(fill.connected(IVec3::ZERO, |x| diagonal.contains(&x)).map(|x| x.len()), Some(3));
+    }
+
+    #[test]
+    fn oversized_fills_are_rejected() {
+        let fill = FloodFill::default();
+        assert_eq!(fill.connected(IVec3::ZERO, |x| x.y == 0), None);
+        assert_eq!(fill.connected(IVec3::ZERO, |x| x.y == 0 && x.x.abs() < 8 && x.z.abs() < 8).map(|x| x.len()), Some(225));
+    }
+}
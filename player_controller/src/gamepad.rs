@@ -0,0 +1,160 @@
+use voxel_engine::math::*;
+
+/// A ring of entries shown while a button is held, from which the player chooses by
+/// tilting a stick or moving the pointer toward an entry and then releasing the button.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RadialMenu {
+    /// The direction in which the player is aiming, or `None` while the menu is closed.
+    aim: Option<Vec2>
+}
+
+impl RadialMenu {
+    /// The largest number of entries that a menu shows.
+    pub const MAX_ENTRIES: usize = 16;
+    /// How far the player must aim from the center before an entry is highlighted.
+    const DEAD_ZONE: f32 = 0.35;
+    /// How far the aim moves for each point of pointer movement.
+    const POINTER_SENSITIVITY: f32 = 0.01;
+
+    /// Whether the menu is being shown.
+    pub fn is_open(&self) -> bool {
+        self.aim.is_some()
+    }
+
+    /// Opens the menu while its button is held, and aims it with the stick or pointer.
+    /// Returns the highlighted entry when the button is released.
+    pub fn update(&mut self, held: bool, stick: Vec2, pointer_delta: Vec2, count: usize) -> Option<usize> {
+        match (held, self.aim) {
+            (true, aim) => {
+                let aim = aim.unwrap_or_default();
+                self.aim = Some(if Self::DEAD_ZONE < stick.length() {
+                    stick.clamp_length_max(1.0)
+                }
+                else {
+                    (aim + Self::POINTER_SENSITIVITY * vec2(pointer_delta.x, -pointer_delta.y)).clamp_length_max(1.0)
+                });
+                None
+            },
+            (false, Some(aim)) => {
+                self.aim = None;
+                Self::entry_at(aim, count)
+            },
+            (false, None) => None
+        }
+    }
+
+    /// Draws the menu around the center of the screen, if it is open. Each entry is a label and a color.
+    pub fn draw(&self, painter: &egui::Painter, entries: &[(String, egui::Color32)]) {
+        /// The distance from the center of the screen to each entry.
+        const RING_RADIUS: f32 = 120.0;
+        /// The radius of the swatch drawn for each entry.
+        const SWATCH_RADIUS: f32 = 16.0;
+
+        let Some(aim) = self.aim else {
+            return;
+        };
+
+        let center = painter.clip_rect().center();
+        let highlighted = Self::entry_at(aim, entries.len());
+        painter.circle_filled(center, RING_RADIUS + 2.0 * SWATCH_RADIUS, egui::Color32::from_black_alpha(128));
+
+        for (index, (label, color)) in entries.iter().enumerate() {
+            let angle = std::f32::consts::TAU * index as f32 / entries.len() as f32;
+            let position = center + RING_RADIUS * egui::vec2(angle.sin(), -angle.cos());
+            let selected = highlighted == Some(index);
+            let radius = [SWATCH_RADIUS, 1.3 * SWATCH_RADIUS][selected as usize];
+
+            painter.circle_filled(position, radius, *color);
+            if selected {
+                painter.circle_stroke(position, radius + 2.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+                painter.text(center, egui::Align2::CENTER_CENTER, label, egui::FontId::proportional(18.0), egui::Color32::WHITE);
+            }
+        }
+
+        painter.line_segment([center, center + RING_RADIUS * egui::vec2(aim.x, -aim.y)], egui::Stroke::new(2.0, egui::Color32::from_white_alpha(160)));
+    }
+
+    /// Gets the entry in the direction of the aim, with the first entry at the top and the rest clockwise.
+    fn entry_at(aim: Vec2, count: usize) -> Option<usize> {
+        (Self::DEAD_ZONE <= aim.length() && 0 < count).then(|| {
+            let angle = aim.x.atan2(aim.y).rem_euclid(std::f32::consts::TAU);
+            (angle / std::f32::consts::TAU * count as f32).round() as usize % count
+        })
+    }
+}
+
+/// The less common controller actions, which gamepad players reach through the action wheel
+/// since a gamepad has too few buttons to bind every action directly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WheelCommand {
+    /// Reverts the most recent edit.
+    Undo,
+    /// Reapplies the most recently undone edit.
+    Redo,
+    /// Switches between placing voxels and sculpting terrain.
+    ToggleSculptMode,
+    /// Switches to the next sculpting operation.
+    CycleSculptOperation,
+    /// Switches to the next sculpting strength.
+    CycleSculptStrength,
+    /// Recolors the connected voxels under the pointer.
+    FloodRecolor,
+    /// Deletes the connected voxels under the pointer.
+    FloodDelete,
+    /// Switches to the next flood fill connectivity.
+    CycleFloodConnectivity,
+    /// Pins or releases a physics entity.
+    FreezeHeldObject,
+    /// Opens or closes the spawn menu.
+    ToggleSpawnMenu,
+    /// Opens or closes the entity inspector.
+    ToggleEntityInspector,
+    /// Opens or closes the key binding menu.
+    ToggleKeyBindings
+}
+
+impl WheelCommand {
+    /// Every command, in the order in which they appear around the wheel.
+    pub const ALL: [Self; 12] = [
+        Self::Undo, Self::Redo, Self::ToggleSculptMode, Self::CycleSculptOperation,
+        Self::CycleSculptStrength, Self::FloodRecolor, Self::FloodDelete, Self::CycleFloodConnectivity,
+        Self::FreezeHeldObject, Self::ToggleSpawnMenu, Self::ToggleEntityInspector, Self::ToggleKeyBindings
+    ];
+
+    /// Gets the human-readable name of the command.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::ToggleSculptMode => "Sculpt mode",
+            Self::CycleSculptOperation => "Sculpt operation",
+            Self::CycleSculptStrength => "Sculpt strength",
+            Self::FloodRecolor => "Flood recolor",
+            Self::FloodDelete => "Flood delete",
+            Self::CycleFloodConnectivity => "Flood connectivity",
+            Self::FreezeHeldObject => "Freeze object",
+            Self::ToggleSpawnMenu => "Spawn menu",
+            Self::ToggleEntityInspector => "Entity inspector",
+            Self::ToggleKeyBindings => "Key bindings"
+        }
+    }
+}
+
+/// Moves focus between the widgets of egui windows and presses the focused widget, so that the
+/// panels of every mod may be used with a gamepad. Focus moves apply to all windows, while presses
+/// reach the windows that are drawn after this call during the same frame.
+pub fn navigate_egui(egui_ctx: &egui::Context, direction: Option<egui::FocusDirection>, activate: bool) {
+    if let Some(direction) = direction {
+        egui_ctx.memory_mut(|x| x.move_focus(direction));
+    }
+
+    if activate {
+        egui_ctx.input_mut(|x| x.events.push(egui::Event::Key {
+            key: egui::Key::Enter,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE
+        }));
+    }
+}
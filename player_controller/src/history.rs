@@ -0,0 +1,173 @@
+use std::collections::*;
+use voxel_engine::math::*;
+
+/// Records voxel edits so that they may be undone and redone.
+/// Edits are grouped into operations, and the oldest operations
+/// are forgotten once the total number of recorded edits exceeds a budget.
+/// Only edits whose previous contents are known may be recorded.
+#[derive(Clone, Debug, Default)]
+pub struct EditHistory {
+    /// The operation that is currently being recorded.
+    pending: Vec<VoxelEdit>,
+    /// Operations that were undone, and may be redone, with the most recent last.
+    redo: Vec<Vec<VoxelEdit>>,
+    /// The total number of edits held in the undo and redo stacks.
+    recorded_edits: usize,
+    /// Operations that may be undone, with the most recent last.
+    undo: VecDeque<Vec<VoxelEdit>>
+}
+
+impl EditHistory {
+    /// The maximum number of individual voxel edits that the history retains.
+    const MAX_RECORDED_EDITS: usize = 1 << 18;
+
+    /// Finishes the pending operation, so that subsequent edits form a new operation.
+    pub fn commit(&mut self) {
+        if !self.pending.is_empty() {
+            self.recorded_edits += self.pending.len();
+            self.undo.push_back(std::mem::take(&mut self.pending));
+
+            while Self::MAX_RECORDED_EDITS < self.recorded_edits && 1 < self.undo.len() {
+                if let Some(oldest) = self.undo.pop_front() {
+                    self.recorded_edits -= oldest.len();
+                }
+            }
+        }
+    }
+
+    /// Forgets every operation, including the pending one.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Adds an edit to the pending operation. This discards any operations that could be redone.
+    pub fn record(&mut self, edit: VoxelEdit) {
+        for operation in self.redo.drain(..) {
+            self.recorded_edits -= operation.len();
+        }
+
+        self.pending.push(edit);
+    }
+
+    /// Removes the most recent operation, and returns the edits needed to revert it, in order.
+    pub fn undo(&mut self) -> Option<Vec<VoxelEdit>> {
+        self.commit();
+        let operation = self.undo.pop_back()?;
+        let reverted = operation.iter().rev().map(VoxelEdit::inverse).collect();
+        self.redo.push(operation);
+        Some(reverted)
+    }
+
+    /// Removes the most recently undone operation, and returns the edits needed to reapply it, in order.
+    pub fn redo(&mut self) -> Option<Vec<VoxelEdit>> {
+        self.commit();
+        let operation = self.redo.pop()?;
+        let reapplied = operation.clone();
+        self.undo.push_back(operation);
+        Some(reapplied)
+    }
+}
+
+/// A change to the contents of a single voxel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VoxelEdit {
+    /// The contents of the voxel after the edit.
+    pub current: VoxelState,
+    /// The contents of the voxel before the edit.
+    pub previous: VoxelState,
+    /// The world-space coordinate of the voxel.
+    pub voxel: IVec3
+}
+
+impl VoxelEdit {
+    /// Gets the edit that reverts this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            current: self.previous,
+            previous: self.current,
+            voxel: self.voxel
+        }
+    }
+}
+
+/// Describes what a voxel contains.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VoxelState {
+    /// The voxel is empty.
+    Empty,
+    /// The voxel is filled with the given material.
+    Filled(u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an edit that fills the given voxel, which was previously empty.
+    fn fill(x: i32, material: u32) -> VoxelEdit {
+        VoxelEdit {
+            current: VoxelState::Filled(material),
+            previous: VoxelState::Empty,
+            voxel: ivec3(x, 0, 0)
+        }
+    }
+
+    #[test]
+    fn undo_reverts_operations_in_reverse_order() {
+        let mut history = EditHistory::default();
+        history.record(fill(0, 1));
+        history.record(fill(1, 1));
+        history.commit();
+        history.record(fill(2, 2));
+
+        assert_eq!(history.undo(), Some(vec![fill(2, 2).inverse()]));
+        assert_eq!(history.undo(), Some(vec![fill(1, 1).inverse(), fill(0, 1).inverse()]));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn redo_reapplies_undone_operations() {
+        let mut history = EditHistory::default();
+        history.record(fill(0, 1));
+        history.commit();
+        history.record(fill(1, 2));
+        history.commit();
+
+        history.undo();
+        history.undo();
+        assert_eq!(history.redo(), Some(vec![fill(0, 1)]));
+        assert_eq!(history.redo(), Some(vec![fill(1, 2)]));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo(), Some(vec![fill(1, 2).inverse()]));
+    }
+
+    #[test]
+    fn recording_discards_undone_operations() {
+        let mut history = EditHistory::default();
+        history.record(fill(0, 1));
+        history.commit();
+        history.record(fill(1, 2));
+        history.commit();
+
+        history.undo();
+        history.record(fill(2, 3));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.recorded_edits, 2);
+        assert_eq!(history.undo(), Some(vec![fill(2, 3).inverse()]));
+        assert_eq!(history.undo(), Some(vec![fill(0, 1).inverse()]));
+    }
+
+    #[test]
+    fn oldest_operations_are_forgotten_past_the_budget() {
+        let mut history = EditHistory::default();
+        history.record(fill(0, 1));
+        history.commit();
+        for x in 0..EditHistory::MAX_RECORDED_EDITS as i32 {
+            history.record(fill(x, 2));
+        }
+        history.commit();
+
+        assert_eq!(history.undo().map(|x| x.len()), Some(EditHistory::MAX_RECORDED_EDITS));
+        assert_eq!(history.undo(), None);
+    }
+}
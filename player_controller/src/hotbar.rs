@@ -0,0 +1,148 @@
+use crate::palette::*;
+
+/// A row of slots, each referencing an item in the palette. Slots that reference an index
+/// past the end of the palette are empty, and fill in as items are added to the palette.
+#[derive(Clone, Debug)]
+pub struct Hotbar {
+    /// The index of the slot that is currently selected.
+    selected_slot: usize,
+    /// The palette index referenced by each slot.
+    slots: [u32; Self::SLOT_COUNT]
+}
+
+impl Hotbar {
+    /// The number of slots in the hotbar.
+    pub const SLOT_COUNT: usize = 9;
+
+    /// Cycles the item referenced by the selected slot through the palette.
+    pub fn cycle_item(&mut self, delta: i32, palette_len: usize) {
+        let item_count = palette_len.max(1) as i64;
+        let item = &mut self.slots[self.selected_slot];
+        *item = (*item as i64 + delta as i64).rem_euclid(item_count) as u32;
+    }
+
+    /// Moves the selection to a neighboring slot.
+    pub fn cycle_slot(&mut self, delta: i32) {
+        self.selected_slot = (self.selected_slot as i64 + delta as i64).rem_euclid(Self::SLOT_COUNT as i64) as usize;
+    }
+
+    /// Selects the slot that references the given item. If no slot references
+    /// the item, it is placed into the currently-selected slot instead.
+    pub fn pick(&mut self, item: u32) {
+        if let Some(slot) = self.slots.iter().position(|&x| x == item) {
+            self.selected_slot = slot;
+        }
+        else {
+            self.slots[self.selected_slot] = item;
+        }
+    }
+
+    /// Selects the slot with the given index.
+    pub fn select_slot(&mut self, slot: usize) {
+        self.selected_slot = slot.min(Self::SLOT_COUNT - 1);
+    }
+
+    /// Gets the palette index of the currently-selected item.
+    pub fn selected_item(&self) -> u32 {
+        self.slots[self.selected_slot]
+    }
+
+    /// Draws the hotbar along the bottom of the screen.
+    pub fn draw(&self, painter: &mut egui::Painter, palette: &Palette) {
+        /// The distance between the hotbar and the screen bottom.
+        const BOTTOM_MARGIN: f32 = 12.0;
+        /// The space between adjacent slots.
+        const SLOT_SPACING: f32 = 4.0;
+        /// The width and height of each slot.
+        const SLOT_SIZE: f32 = 40.0;
+
+        let clip_rect = painter.clip_rect();
+        let total_width = Self::SLOT_COUNT as f32 * (SLOT_SIZE + SLOT_SPACING) - SLOT_SPACING;
+        let left = clip_rect.center().x - 0.5 * total_width;
+        let top = clip_rect.bottom() - BOTTOM_MARGIN - SLOT_SIZE;
+
+        for (slot, &item) in self.slots.iter().enumerate() {
+            let min = egui::pos2(left + slot as f32 * (SLOT_SIZE + SLOT_SPACING), top);
+            let slot_rect = egui::Rect::from_min_size(min, egui::Vec2::splat(SLOT_SIZE));
+            painter.rect_filled(slot_rect, 3.0, egui::Color32::from_black_alpha(96));
+
+            if let Some(entry) = palette.get(item) {
+                let [r, g, b, a] = entry.color;
+                painter.rect_filled(slot_rect.shrink(6.0), 2.0, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+
+                if let Some(icon) = &entry.icon {
+                    painter.text(slot_rect.center(), egui::Align2::CENTER_CENTER, icon, egui::FontId::proportional(18.0), egui::Color32::WHITE);
+                }
+            }
+
+            painter.text(slot_rect.left_top() + egui::vec2(3.0, 1.0), egui::Align2::LEFT_TOP, (slot + 1).to_string(), egui::FontId::proportional(10.0), egui::Color32::WHITE);
+
+            if slot == self.selected_slot {
+                painter.rect_stroke(slot_rect.expand(1.0), 3.0, egui::Stroke::new(2.0, egui::Color32::WHITE));
+            }
+        }
+    }
+}
+
+impl Default for Hotbar {
+    fn default() -> Self {
+        Self {
+            selected_slot: 0,
+            slots: std::array::from_fn(|slot| slot as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_past_the_end_of_the_palette_are_empty() {
+        let palette = Palette::default();
+        let mut hotbar = Hotbar::default();
+        hotbar.select_slot(palette.len() - 1);
+        assert!(palette.get(hotbar.selected_item()).is_some());
+
+        hotbar.cycle_slot(1);
+        assert_eq!(hotbar.selected_item() as usize, palette.len());
+        assert!(palette.get(hotbar.selected_item()).is_none());
+    }
+
+    #[test]
+    fn slots_wrap_around() {
+        let mut hotbar = Hotbar::default();
+        hotbar.cycle_slot(-1);
+        assert_eq!(hotbar.selected_item() as usize, Hotbar::SLOT_COUNT - 1);
+
+        hotbar.cycle_slot(2);
+        assert_eq!(hotbar.selected_item(), 1);
+
+        hotbar.select_slot(100);
+        assert_eq!(hotbar.selected_item() as usize, Hotbar::SLOT_COUNT - 1);
+    }
+
+    #[test]
+    fn items_cycle_through_the_palette() {
+        let mut hotbar = Hotbar::default();
+        hotbar.cycle_item(-1, 5);
+        assert_eq!(hotbar.selected_item(), 4);
+
+        hotbar.cycle_item(3, 5);
+        assert_eq!(hotbar.selected_item(), 2);
+
+        hotbar.cycle_item(1, 0);
+        assert_eq!(hotbar.selected_item(), 0);
+    }
+
+    #[test]
+    fn picking_selects_or_replaces_a_slot() {
+        let mut hotbar = Hotbar::default();
+        hotbar.pick(6);
+        assert_eq!((hotbar.selected_slot, hotbar.selected_item()), (6, 6));
+
+        hotbar.pick(20);
+        assert_eq!((hotbar.selected_slot, hotbar.selected_item()), (6, 20));
+        assert_eq!(hotbar.slots.iter().filter(|&&x| x == 6).count(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::physics_gun::*;
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+use voxel_std_types::targeting::*;
+
+/// Shows what is known about the physics entity most recently under the pointer.
+/// The engine does not report the pose, velocity, mass, or sleep state of entities
+/// to mods, so the inspector is limited to what raycasts and the physics gun reveal.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EntityInspector {
+    /// Whether the inspector window is shown.
+    pub open: bool,
+    /// The entity being inspected, which remains shown while the pointer is elsewhere.
+    target: Option<InspectedEntity>
+}
+
+impl EntityInspector {
+    /// Records the entity under the pointer, if any, as the one to inspect.
+    pub fn observe(&mut self, pointer_ray: &Ray, hit_result: Option<&RaycastHit>) {
+        if let Some(hit) = hit_result {
+            if let RaycastObject::Entity { id } = hit.object {
+                let held = HeldObject::from_hit(pointer_ray, hit, id);
+                self.target = Some(InspectedEntity {
+                    anchor: FrozenObject { contact_point: held.contact_point, id, target_position: held.target_position(pointer_ray) },
+                    distance: hit.distance,
+                    hit_point: to_voxel_space(pointer_ray.position) + hit.distance * pointer_ray.direction,
+                    voxel: hit.voxel
+                });
+            }
+        }
+    }
+
+    /// Draws the inspector window, if it is open, and gets the command chosen by the player.
+    /// The name of the object chosen in the spawn menu is shown, since duplicates are of that kind.
+    pub fn draw(&mut self, egui_ctx: &egui::Context, physics_gun: &PhysicsGun, spawn_name: Option<&str>) -> Option<InspectorCommand> {
+        /// How far above the inspected entity's hit point a duplicate is spawned, in voxels.
+        const DUPLICATE_HEIGHT: f32 = 4.0;
+
+        let mut command = None;
+        let target = self.target;
+
+        egui::Window::new("Entity inspector").open(&mut self.open).resizable(false).show(egui_ctx, |ui| {
+            let Some(target) = target else {
+                ui.label("Aim at a physics entity to inspect it");
+                return;
+            };
+
+            let id = target.anchor.id;
+            let frozen = physics_gun.frozen.is_some_and(|x| x.id == id);
+            let held = physics_gun.held.is_some_and(|x| x.id == id);
+            let state = match (held, frozen) {
+                (true, _) => "held",
+                (_, true) => "frozen",
+                _ => "free"
+            };
+
+            egui::Grid::new("entity_inspector").num_columns(2).show(ui, |ui| {
+                ui.label("ID");
+                ui.label(id.to_string());
+                ui.end_row();
+                ui.label("State");
+                ui.label(state);
+                ui.end_row();
+                ui.label("Hit point");
+                ui.label(format!("{:.2}, {:.2}, {:.2}", target.hit_point.x, target.hit_point.y, target.hit_point.z));
+                ui.end_row();
+                ui.label("Hit voxel (local)");
+                ui.label(format!("{}, {}, {}", target.voxel.x, target.voxel.y, target.voxel.z));
+                ui.end_row();
+                ui.label("Distance");
+                ui.label(format!("{:.2}", target.distance));
+                ui.end_row();
+            });
+
+            ui.weak("Position, velocity, mass, and sleep state are not reported by the engine.");
+
+            ui.horizontal(|ui| {
+                if frozen {
+                    if ui.button("Unfreeze").clicked() {
+                        command = Some(InspectorCommand::Unfreeze);
+                    }
+                }
+                else if ui.add_enabled(!held, egui::Button::new("Freeze")).clicked() {
+                    command = Some(InspectorCommand::Freeze(target.anchor));
+                }
+
+                if ui.button("Delete").on_hover_text("The engine cannot remove entities, so this drags the entity far below where it was seen").clicked() {
+                    command = Some(InspectorCommand::Delete(target.anchor));
+                }
+
+                let duplicate_hint = format!("The engine does not report entity kinds, so this spawns the object chosen in the spawn menu ({}) above the entity", spawn_name.unwrap_or("none"));
+                if ui.add_enabled(spawn_name.is_some(), egui::Button::new("Duplicate")).on_hover_text(duplicate_hint).clicked() {
+                    command = Some(InspectorCommand::Duplicate(target.hit_point + DUPLICATE_HEIGHT * Vec3A::Y));
+                }
+            });
+        });
+
+        command
+    }
+}
+
+/// An action requested through the inspector window.
+#[derive(Copy, Clone, Debug)]
+pub enum InspectorCommand {
+    /// Removes the inspected entity from play, by the point at which it was seen.
+    Delete(FrozenObject),
+    /// Spawns the object chosen in the spawn menu at the given voxel-space position.
+    Duplicate(Vec3A),
+    /// Pins the inspected entity where it was seen.
+    Freeze(FrozenObject),
+    /// Releases the frozen entity.
+    Unfreeze
+}
+
+/// The information gathered about an inspected entity when it was last under the pointer.
+#[derive(Copy, Clone, Debug)]
+struct InspectedEntity {
+    /// The pin that would hold the entity where it was seen.
+    anchor: FrozenObject,
+    /// The distance from the camera to the hit point.
+    distance: f32,
+    /// The voxel-space point at which the pointer struck the entity.
+    hit_point: Vec3A,
+    /// The body-local voxel that the pointer struck.
+    voxel: IVec3
+}
@@ -1,3 +1,18 @@
+use crate::brush::*;
+use crate::flood_fill::*;
+use crate::gamepad::*;
+use crate::history::*;
+use crate::hotbar::*;
+use crate::inspector::*;
+use crate::material_cache::*;
+use crate::notices::*;
+use crate::palette::*;
+use crate::physics_gun::*;
+use crate::rebinding::*;
+use crate::sculpt::*;
+use crate::spawn_menu::*;
+use crate::touch::*;
+use std::collections::*;
 use std::time::*;
 use voxel_engine::*;
 use voxel_engine::input::*;
@@ -5,103 +20,347 @@ use voxel_engine::math::*;
 use voxel_engine::physics::*;
 use voxel_engine::player::*;
 use voxel_engine::timing::*;
+use voxel_std_types::bindings::*;
+use voxel_std_types::editing::*;
+use voxel_std_types::gestures::*;
+use voxel_std_types::overlay::*;
+use voxel_std_types::palette::*;
+use voxel_std_types::recording::*;
+use voxel_std_types::spawning::*;
+use voxel_std_types::targeting::*;
 use wings::*;
 
+/// Determines the shape and size of voxel edits.
+mod brush;
+/// Finds connected groups of voxels that share a material.
+mod flood_fill;
+/// Radial menus and menu navigation for players using gamepads.
+mod gamepad;
+/// Records voxel edits so that they may be undone.
+mod history;
+/// Displays and manages the row of quick-access item slots.
+mod hotbar;
+/// Shows information about the physics entity under the pointer.
+mod inspector;
+/// Tracks the materials of voxels edited through this mod.
+mod material_cache;
+/// Shows messages that disappear after a while.
+mod notices;
+/// Loads and stores the items that the player may place.
+mod palette;
+/// Holds, freezes, and throws physics objects.
+mod physics_gun;
+/// Lists the actions of every mod so that they may be rebound.
+mod rebinding;
+/// Raises, lowers, smooths, and flattens terrain columns.
+mod sculpt;
+/// Lists the physics objects that the player may spawn.
+mod spawn_menu;
+/// On-screen joysticks and buttons for touch and pen input.
+mod touch;
+
 instantiate_systems!(Client, [PlayerController]);
 
 /// Implements a basic first-person character controller for movement.
-#[export_system]
+#[export_system(ActionRegistry, ItemPalette, ObjectCatalog, VoxelEditor)]
 pub struct PlayerController {
+    /// The wheel of less common commands, opened by holding a button.
+    action_wheel: RadialMenu,
+    /// The brush used when placing or deleting voxels.
+    brush: Brush,
     /// The context handle.
     ctx: WingsContextHandle<Self>,
-    /// Holds data about an object currently being dragged.
-    dragged_object: Option<DraggedObject>,
-    /// The kind of physics object to spawn next.
-    object_kind: u32,
-    /// The item that the user has currently selected.
-    selected_item: u32,
+    /// Finds the voxels affected by flood fills.
+    flood_fill: FloodFill,
+    /// The input for the current frame, as captured or replayed by the input recorder.
+    frame_input: InputSnapshot,
+    /// The record of edits that may be undone or redone.
+    history: EditHistory,
+    /// The quick-access slots from which the user selects items.
+    hotbar: Hotbar,
+    /// Shows information about the physics entity under the pointer.
+    inspector: EntityInspector,
+    /// The interaction chosen for the currently-held place or drag input.
+    interaction: Option<Interaction>,
+    /// The arrangement of the player's keyboard, by which the default keys were placed.
+    layout: KeyboardLayout,
+    /// The known materials of voxels that were edited through this mod.
+    material_cache: MaterialCache,
+    /// The wheel of palette items, opened by holding a button.
+    material_wheel: RadialMenu,
+    /// Whether gamepad buttons are being used to move through menus during this frame.
+    navigating_ui: bool,
+    /// Messages about configuration problems and detected settings that are shown to the user for a while.
+    notices: NoticeBoard,
+    /// The set of items that the user may place.
+    palette: Palette,
+    /// Holds, freezes, and throws physics objects.
+    physics_gun: PhysicsGun,
+    /// Projects world-space previews onto the screen.
+    projection: ScreenProjection,
+    /// Allows the player to bind actions to different inputs.
+    rebinding: RebindingMenu,
+    /// The recording or replay for which the controller state was last reset.
+    recorder_session: u32,
+    /// Recognizes the chord that reapplies the most recently undone edit.
+    redo_chord: Gesture,
+    /// The actions of every mod, in registration order.
+    registered_actions: Vec<BoundAction>,
+    /// Whether the pointer was locked when a menu released it, so that it is locked again once every menu closes.
+    relock_pointer: bool,
+    /// Reshapes terrain when sculpting mode is enabled.
+    sculptor: Sculptor,
+    /// Lists the physics objects that may be spawned, and tracks the chosen one.
+    spawn_menu: SpawnMenu,
+    /// The on-screen controls used with touch and pen input.
+    touch: TouchControls,
+    /// Recognizes the chord that reverts the most recent edit.
+    undo_chord: Gesture,
     /// Holds handles for accessing user input.
     user_actions: UserActions,
     /// The time at which the user may next place or destroy voxels.
     wait_for_placement_until: Duration,
     /// Whether the user was placing or deleting voxels.
-    was_placing: bool
+    was_placing: bool,
+    /// The command chosen from the action wheel during this frame, if any.
+    wheel_command: Option<WheelCommand>
 }
 
 impl PlayerController {
-    /// Draws a crosshair and text related to the character controls.
-    fn draw_controls_gui(&mut self, pointer_locked: bool) {
+    /// Draws a crosshair, target previews, and text related to the character controls.
+    fn draw_controls_gui(&mut self, pointer_locked: bool, transform: &Transform, pointer_target: Option<&(Ray, Option<RaycastHit>)>) {
         let egui_system = self.ctx.get::<dyn egui::Egui>();
         let egui_ctx = egui_system.context();
 
         let mut painter = egui_ctx.layer_painter(egui::LayerId::background());
+        if let Some((pointer_ray, hit_result)) = pointer_target {
+            if let Some(pointer_position) = egui_ctx.input(|x| x.pointer.latest_pos()) {
+                self.projection.calibrate(transform, pointer_ray.direction, pointer_position, painter.clip_rect());
+            }
+
+            if let Some(hit) = hit_result.as_ref().filter(|x| x.object == (RaycastObject::World { })) {
+                self.draw_target_preview(&painter, transform, pointer_ray, hit);
+            }
+
+            if let Some(held) = self.physics_gun.held {
+                self.draw_tether(&painter, transform, to_voxel_space(held.target_position(pointer_ray)));
+            }
+        }
+
         if pointer_locked {
             Self::draw_crosshairs(&mut painter);
         }
 
-        let selected_item = match self.selected_item % 8 {
-            0 => "Red light",
-            1 => "Green light",
-            2 => "Blue light",
-            3 => "White light",
-            4 => "Red plastic",
-            5 => "Green plastic",
-            6 => "Blue plastic",
-            7 => "White plastic",
-            _ => unreachable!()
-        };
+        self.hotbar.draw(&mut painter, &self.palette);
+        self.touch.draw(&painter);
+
+        if self.material_wheel.is_open() {
+            let materials = self.palette.entries().iter()
+                .take(RadialMenu::MAX_ENTRIES)
+                .map(|x| (x.name.clone(), egui::Color32::from_rgba_unmultiplied(x.color[0], x.color[1], x.color[2], x.color[3])))
+                .collect::<Vec<_>>();
+            self.material_wheel.draw(&painter, &materials);
+        }
 
-        Self::draw_item_text(&mut painter, selected_item);
+        if self.action_wheel.is_open() {
+            let commands = WheelCommand::ALL.map(|x| (x.name().to_string(), egui::Color32::from_gray(90)));
+            self.action_wheel.draw(&painter, &commands);
+        }
+        self.spawn_menu.draw(&egui_ctx);
+        self.rebinding.draw(&egui_ctx, &*self.ctx.get::<dyn Input>(), &self.registered_actions);
+        self.notices.extend(self.rebinding.detect_layout(&egui_ctx));
+
+        match self.inspector.draw(&egui_ctx, &self.physics_gun, self.spawn_menu.selected().map(|x| x.name.as_str())) {
+            Some(InspectorCommand::Delete(anchor)) => self.physics_gun.discard(anchor.id, anchor.contact_point, to_voxel_space(anchor.target_position)),
+            Some(InspectorCommand::Duplicate(position)) => if let Some(object) = self.spawn_menu.selected() {
+                self.ctx.get::<dyn Player>().spawn_physics_object(position.into(), object.kind);
+            },
+            Some(InspectorCommand::Freeze(frozen)) => self.physics_gun.freeze(frozen),
+            Some(InspectorCommand::Unfreeze) => self.physics_gun.unfreeze(),
+            None => {}
+        }
+
+        if let Some(entry) = self.palette.get(self.hotbar.selected_item()) {
+            let tool = if self.sculptor.enabled {
+                self.sculptor.describe(self.brush.radius)
+            }
+            else {
+                self.brush.describe()
+            };
+
+            let selected_item = match &entry.icon {
+                Some(icon) => format!("{icon} {} ({tool})", entry.name),
+                None => format!("{} ({tool})", entry.name)
+            };
+
+            Self::draw_item_text(&mut painter, &selected_item);
+        }
+
+        self.notices.update(self.frame_input.delta_time());
+        self.notices.draw(&mut painter);
     }
 
-    /// Handles interaction and dragging with physics objects.
+    /// Outlines the voxel under the pointer, and shows a translucent preview of the voxel that would be placed.
+    fn draw_target_preview(&self, painter: &egui::Painter, transform: &Transform, pointer_ray: &Ray, hit: &RaycastHit) {
+        /// The opacity of the placement preview.
+        const PREVIEW_ALPHA: u8 = 96;
+
+        let hit_min = hit.voxel.as_vec3a();
+        self.projection.draw_box_outline(painter, transform, hit_min, hit_min + Vec3A::ONE, egui::Stroke::new(1.5, egui::Color32::WHITE));
+
+        if self.sculptor.enabled {
+            let radius = ivec3(self.brush.radius as i32, 0, self.brush.radius as i32);
+            let footprint_min = (hit.voxel - radius + IVec3::Y).as_vec3a();
+            let footprint_max = (hit.voxel + radius + IVec3::ONE).as_vec3a();
+            self.projection.draw_box_outline(painter, transform, footprint_min, footprint_max, egui::Stroke::new(1.0, egui::Color32::from_white_alpha(160)));
+            return;
+        }
+
+        let target = placement_voxel(pointer_ray, hit);
+        if 0 < self.brush.radius {
+            let (min, max) = self.brush.bounds(target);
+            self.projection.draw_box_outline(painter, transform, min.as_vec3a(), max.as_vec3a(), egui::Stroke::new(1.0, egui::Color32::from_white_alpha(160)));
+        }
+
+        if !voxel_intersects_point(target, to_voxel_space(pointer_ray.position), PLAYER_CLEARANCE) {
+            if let Some(entry) = self.palette.get(self.hotbar.selected_item()) {
+                let [r, g, b, _] = entry.color;
+                let target_min = target.as_vec3a();
+                self.projection.draw_box_faces(painter, transform, target_min, target_min + Vec3A::ONE, egui::Color32::from_rgba_unmultiplied(r, g, b, PREVIEW_ALPHA));
+            }
+        }
+    }
+
+    /// Draws a line from the player's hand to the point toward which a held entity is pulled.
+    fn draw_tether(&self, painter: &egui::Painter, transform: &Transform, target: Vec3A) {
+        /// The camera-space position from which the tether extends.
+        const HAND_OFFSET: Vec3A = Vec3A::new(0.3, -0.25, 0.5);
+        /// The radius of the marker drawn around the held point, in points.
+        const MARKER_RADIUS: f32 = 4.0;
+
+        let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(120, 200, 255));
+        let hand = to_voxel_space(transform.position) + transform.rotation * HAND_OFFSET;
+        self.projection.draw_line(painter, transform, hand, target, stroke);
+
+        if let Some(position) = self.projection.project(transform, painter.clip_rect(), target) {
+            painter.circle_stroke(position, MARKER_RADIUS, stroke);
+        }
+    }
+
+    /// Decides whether a new press of the place or drag inputs should place voxels or drag an
+    /// entity, since both share the same default bindings. The choice is made once per press
+    /// based upon the object under the pointer, and lasts until both inputs are released.
+    fn resolve_interaction(&mut self, hit_result: Option<&RaycastHit>) {
+        let input = &self.frame_input;
+        let drag_physics_entity = input.digital(self.user_actions.drag_physics_entity);
+        let place_voxels = input.digital(self.user_actions.place_voxels);
+        let touch_grab = self.touch.button(TouchButton::Grab);
+        let touch_place = self.touch.button(TouchButton::Place);
+
+        if !drag_physics_entity.held && !place_voxels.held && !touch_grab.held && !touch_place.held {
+            self.interaction = None;
+        }
+        else if self.interaction.is_none() {
+            self.interaction = match hit_result.map(|x| x.object) {
+                Some(RaycastObject::Entity { .. }) if drag_physics_entity.pressed || touch_grab.pressed => Some(Interaction::DragEntity),
+                Some(RaycastObject::World { }) if place_voxels.pressed || touch_place.pressed => Some(Interaction::PlaceVoxels),
+                _ => None
+            };
+        }
+    }
+
+    /// Handles grabbing, moving, rotating, freezing, throwing, and deleting physics objects.
     fn handle_object_interaction(&mut self, pointer_ray: &Ray, hit_result: Option<&RaycastHit>) {
-        let input = self.ctx.get::<dyn Input>();
+        /// How quickly a held entity turns with the look stick, in pointer points per second.
+        const STICK_ROTATION_SPEED: f32 = 600.0;
+
+        let delta_time = self.frame_input.delta_time();
+        let input = &self.frame_input;
+        let delete_entity = input.digital(self.user_actions.delete_entity);
+        let drag_physics_entity = input.digital(self.user_actions.drag_physics_entity);
+        let freeze_held_object = input.digital(self.user_actions.freeze_held_object);
+        let rotate_held_object = input.digital(self.user_actions.rotate_held_object);
+        let spawn_physics_entity = input.digital(self.user_actions.spawn_physics_entity);
+        let throw_held_object = input.digital(self.user_actions.throw_held_object);
+        let look = vec2(input.analog(self.user_actions.look_horizontal), -input.analog(self.user_actions.look_vertical));
+        let pointer_delta = input.pointer_delta();
+        let scroll_delta = input.scroll_delta();
+
+        let touch_grab = self.touch.button(TouchButton::Grab);
+        let dragging = drag_physics_entity.held || touch_grab.held;
         let player = self.ctx.get::<dyn Player>();
-        let drag_physics_entity = input.get(self.user_actions.drag_physics_entity);
-        let spawn_physics_entity = input.get(self.user_actions.spawn_physics_entity);
-        
-        if spawn_physics_entity.pressed {
+        self.physics_gun.update_velocity(to_voxel_space(pointer_ray.position), delta_time);
+
+        if let Some(object) = self.spawn_menu.selected().filter(|_| spawn_physics_entity.pressed) {
             let distance = hit_result.map(|x| x.distance).unwrap_or(f32::MAX);
             let position = pointer_ray.position + ((distance - 30.0).max(0.0).min(50.0) * pointer_ray.direction).into();
-            self.object_kind = self.object_kind.wrapping_add(1);
-            player.spawn_physics_object(position, self.object_kind);
+            player.spawn_physics_object(position, object.kind);
         }
 
-        if !drag_physics_entity.held && self.dragged_object.is_some() {
-            player.drag_physics_object(None);
-            self.dragged_object = None;
+        if !dragging {
+            self.physics_gun.release();
         }
-        else if drag_physics_entity.pressed && self.dragged_object.is_none() {
+        else if (drag_physics_entity.pressed || touch_grab.pressed) && self.physics_gun.held.is_none() && self.interaction == Some(Interaction::DragEntity) {
             if let Some(hit) = hit_result {
                 if let RaycastObject::Entity { id } = hit.object {
-                    let contact_point = hit.voxel.as_vec3a() + Vec3A::splat(0.5);
-                    self.dragged_object = Some(DraggedObject { contact_point, id, distance: hit.distance });
+                    self.physics_gun.grab(HeldObject::from_hit(pointer_ray, hit, id));
                 }
             }
         }
 
-        if let Some(dragged) = self.dragged_object {
-            let target_position = pointer_ray.position + (dragged.distance * pointer_ray.direction).into();
-            player.drag_physics_object(Some(DragEntity { contact_point: dragged.contact_point, id: dragged.id, target_position }));
+        self.physics_gun.push_pull(scroll_delta.y as f32 + self.touch.pinch_steps());
+
+        if rotate_held_object.held {
+            self.physics_gun.rotate(pointer_delta + STICK_ROTATION_SPEED * delta_time * look);
+        }
+
+        if freeze_held_object.pressed || self.wheel_command == Some(WheelCommand::FreezeHeldObject) {
+            self.physics_gun.toggle_freeze(pointer_ray);
+        }
+        else if throw_held_object.pressed || self.touch.button(TouchButton::Throw).pressed {
+            self.physics_gun.throw(pointer_ray);
+        }
+        else if delete_entity.pressed {
+            if let Some(held) = self.physics_gun.held {
+                self.physics_gun.discard(held.id, held.contact_point, to_voxel_space(held.target_position(pointer_ray)));
+            }
+            else if let Some(hit) = hit_result {
+                if let RaycastObject::Entity { id } = hit.object {
+                    let struck = HeldObject::from_hit(pointer_ray, hit, id);
+                    self.physics_gun.discard(id, struck.contact_point, to_voxel_space(struck.target_position(pointer_ray)));
+                }
+            }
+        }
+
+        if let Some(drag) = self.physics_gun.update_drag(pointer_ray, delta_time) {
+            player.drag_physics_object(drag);
         }
     }
 
     /// Places or destroys voxels according to the player's input.
-    fn handle_player_place_destroy(&mut self, hit_result: Option<&RaycastHit>) {
-        let input = self.ctx.get::<dyn Input>();
-        let delete_voxels = input.get(self.user_actions.delete_voxels);
-        let place_voxels = input.get(self.user_actions.place_voxels);
-        let now = self.ctx.get::<dyn FrameTiming>().last_frame();        
+    /// New voxels are placed against the face of the voxel that the pointer hit. The pointer ray
+    /// passed through that voxel, so it is known to be empty, and placing into it may be undone.
+    fn handle_player_place_destroy(&mut self, pointer_ray: &Ray, hit_result: Option<&RaycastHit>) {
+        let input = &self.frame_input;
+        let delete_voxels = input.digital(self.user_actions.delete_voxels);
+        let place_voxels = input.digital(self.user_actions.place_voxels);
+        let touch_delete = self.touch.button(TouchButton::Delete);
+        let touch_place = self.touch.button(TouchButton::Place);
+        let delete_pressed = delete_voxels.pressed || touch_delete.pressed;
+        let delete_held = delete_voxels.held || touch_delete.held;
+        let place_pressed = place_voxels.pressed || touch_place.pressed;
+        let place_held = place_voxels.held || touch_place.held;
+        let place_allowed = self.interaction == Some(Interaction::PlaceVoxels);
+        let now = self.frame_input.time();
 
         if let Some(hit) = hit_result {
-            if hit.object == (RaycastObject::World { }) && self.wait_for_placement_until <= now { //self.dragged_object.is_none() && 
-                let delete = if delete_voxels.pressed {
+            if hit.object == (RaycastObject::World { }) && self.wait_for_placement_until <= now {
+                let delete = if delete_pressed {
                     self.wait_for_placement_until = now + Duration::from_secs_f32(0.25);
                     self.was_placing = true;
                     true
                 }
-                else if self.was_placing && delete_voxels.held {
+                else if self.was_placing && delete_held {
                     self.wait_for_placement_until = now + Duration::from_secs_f32(0.05);
                     true
                 }
@@ -110,15 +369,17 @@ impl PlayerController {
                 };
     
                 if delete {
-                    self.ctx.get::<dyn Player>().delete_voxels_at(hit.voxel);
+                    for voxel in self.brush.voxels(hit.voxel) {
+                        self.delete_voxel(voxel);
+                    }
                 }
                 else {
-                    let place = if place_voxels.pressed {
+                    let place = if place_pressed && place_allowed {
                         self.wait_for_placement_until = now + Duration::from_secs_f32(0.25);
                         self.was_placing = true;
                         true
                     }
-                    else if self.was_placing && place_voxels.held {
+                    else if self.was_placing && place_held && place_allowed {
                         self.wait_for_placement_until = now + Duration::from_secs_f32(0.05);
                         true
                     }
@@ -126,31 +387,238 @@ impl PlayerController {
                         false
                     };
     
-                    if place {
-                        self.ctx.get::<dyn Player>().place_voxels_at(hit.voxel, self.selected_item);
+                    let target = placement_voxel(pointer_ray, hit);
+                    let eye = to_voxel_space(pointer_ray.position);
+                    let maybe_material = self.palette.get(self.hotbar.selected_item()).map(|x| x.material);
+
+                    if self.sculptor.enabled {
+                        if place {
+                            self.sculpt_terrain(hit, eye, maybe_material);
+                        }
+                    }
+                    else if let Some(material) = maybe_material.filter(|_| place) {
+                        for voxel in self.brush.voxels(target) {
+                            if !voxel_intersects_point(voxel, eye, PLAYER_CLEARANCE) {
+                                self.edit_voxel(voxel, VoxelState::Filled(material), (voxel == target).then_some(VoxelState::Empty));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        self.was_placing &= delete_held || place_held;
+
+        if !self.was_placing {
+            self.history.commit();
+        }
+    }
+
+    /// Changes the heights of the terrain columns around the hit voxel, filling raised columns with the given material.
+    fn sculpt_terrain(&mut self, hit: &RaycastHit, eye: Vec3A, maybe_material: Option<u32>) {
+        self.sculptor.begin_stroke(hit.voxel);
+
+        let raycaster = self.ctx.get::<dyn Raycaster>();
+        let mut heights = HashMap::new();
+
+        for column in Sculptor::probed_columns(hit.voxel, self.brush.radius) {
+            let probe = Sculptor::probe_ray(hit.voxel, column);
+            if let Some(height) = raycaster.cast_world(&probe).and_then(|x| Sculptor::surface_height(hit.voxel, x.voxel)) {
+                heights.insert(column, height);
+            }
+        }
+
+        drop(raycaster);
+
+        for (column, height, goal) in self.sculptor.sculpt(hit.voxel, self.brush.radius, &heights) {
+            for voxel in Sculptor::removed_voxels(column, height, goal) {
+                self.delete_voxel(voxel);
+            }
+
+            if let Some(material) = maybe_material {
+                for voxel in Sculptor::added_voxels(column, height, goal) {
+                    if !voxel_intersects_point(voxel, eye, PLAYER_CLEARANCE) {
+                        self.place_voxel(voxel, material);
                     }
                 }
             }
         }
+    }
+
+    /// Sets the contents of a voxel without recording the change in the edit history.
+    fn apply_edit(&mut self, edit: VoxelEdit) {
+        match edit.current {
+            VoxelState::Empty => {
+                self.ctx.get::<dyn Player>().delete_voxels_at(edit.voxel);
+                self.material_cache.record_deleted(edit.voxel);
+            },
+            VoxelState::Filled(material) => {
+                self.ctx.get::<dyn Player>().place_voxels_at(edit.voxel, material);
+                self.material_cache.record_placed(edit.voxel, material);
+            }
+        }
+    }
+
+    /// Sets the contents of a voxel, recording the change if the previous contents of the voxel are given or were
+    /// written by this mod. The engine cannot report what any other voxel contains, so changes to such voxels cannot
+    /// be reverted. They are left out of the history, which remains valid since no recorded edit touches those voxels.
+    fn edit_voxel(&mut self, voxel: IVec3, current: VoxelState, maybe_previous: Option<VoxelState>) {
+        let maybe_known_previous = maybe_previous.or_else(|| self.material_cache.state(voxel));
+        let edit = VoxelEdit {
+            current,
+            previous: maybe_known_previous.unwrap_or(current),
+            voxel
+        };
+
+        if maybe_known_previous.is_some() {
+            self.history.record(edit);
+        }
+
+        self.apply_edit(edit);
+    }
+
+    /// Deletes the given voxel. The change may only be undone if the voxel was last written by this mod.
+    fn delete_voxel(&mut self, voxel: IVec3) {
+        self.edit_voxel(voxel, VoxelState::Empty, None);
+    }
+
+    /// Fills the given voxel with a material. The change may only be undone if the voxel was last written by this mod.
+    fn place_voxel(&mut self, voxel: IVec3, material: u32) {
+        self.edit_voxel(voxel, VoxelState::Filled(material), None);
+    }
+
+    /// Undoes or redoes the most recent operation, if the user requested it.
+    fn handle_undo_redo(&mut self) {
+        let delta_time = self.frame_input.delta_time();
+        let redo = self.redo_chord.update(&self.frame_input, delta_time);
+        let undo = self.undo_chord.update(&self.frame_input, delta_time);
+
+        let maybe_edits = if undo.pressed || self.wheel_command == Some(WheelCommand::Undo) {
+            self.history.undo()
+        }
+        else if redo.pressed || self.wheel_command == Some(WheelCommand::Redo) {
+            self.history.redo()
+        }
+        else {
+            None
+        };
+
+        for edit in maybe_edits.into_iter().flatten() {
+            self.apply_edit(edit);
+        }
+    }
+
+    /// Updates the brush shape, fill, and radius according to the player's input. While the modifier is held,
+    /// the scroll wheel and the shape and fill buttons change the radius instead, so that gamepads may also resize
+    /// the brush. Returns whether the modifier is held.
+    fn update_brush(&mut self) -> bool {
+        let input = &self.frame_input;
+        let modifier = input.digital(self.user_actions.modifier);
+        let cycle_brush_shape = input.digital(self.user_actions.cycle_brush_shape);
+        let toggle_brush_hollow = input.digital(self.user_actions.toggle_brush_hollow);
+        let scroll_delta = input.scroll_delta();
+
+        if cycle_brush_shape.pressed && !modifier.held && !self.navigating_ui {
+            self.brush.shape = self.brush.shape.next();
+        }
 
-        self.was_placing &= delete_voxels.held || place_voxels.held;
+        self.brush.hollow ^= toggle_brush_hollow.pressed && !modifier.held && !self.navigating_ui;
+
+        if modifier.held {
+            let button_delta = [0, 1][cycle_brush_shape.pressed as usize] + [0, -1][toggle_brush_hollow.pressed as usize];
+            self.brush.adjust_radius(scroll_delta.y + [button_delta, 0][self.navigating_ui as usize]);
+        }
+
+        modifier.held
     }
 
-    /// Updates the player's position based upon user input.
-    /// Returns the player's new transform.
+    /// Recolors or deletes the voxels connected to the voxel under the pointer that share its material,
+    /// if the user requested it. Only voxels with materials known to the material cache are connected.
+    fn handle_flood_fill(&mut self, hit_result: Option<&RaycastHit>) {
+        let input = &self.frame_input;
+        let cycle_flood_connectivity = input.digital(self.user_actions.cycle_flood_connectivity);
+        let flood_delete = input.digital(self.user_actions.flood_delete).pressed || self.wheel_command == Some(WheelCommand::FloodDelete);
+        let flood_recolor = input.digital(self.user_actions.flood_recolor).pressed || self.wheel_command == Some(WheelCommand::FloodRecolor);
+
+        if cycle_flood_connectivity.pressed || self.wheel_command == Some(WheelCommand::CycleFloodConnectivity) {
+            self.flood_fill.connectivity = self.flood_fill.connectivity.next();
+            self.notices.push(self.flood_fill.describe());
+        }
+
+        let Some(hit) = hit_result.filter(|x| x.object == (RaycastObject::World { }) && (flood_delete || flood_recolor)) else {
+            return;
+        };
+
+        let Some(material) = self.material_cache.get(hit.voxel) else {
+            self.notices.push("The material of the targeted voxel is unknown".to_string());
+            return;
+        };
+
+        let maybe_replacement = self.palette.get(self.hotbar.selected_item()).map(|x| x.material);
+        if flood_recolor && !flood_delete && maybe_replacement.is_none() {
+            self.notices.push("Select an item to recolor with".to_string());
+            return;
+        }
+
+        match self.flood_fill.connected(hit.voxel, |x| self.material_cache.get(x) == Some(material)) {
+            Some(voxels) => {
+                self.history.commit();
+
+                if flood_delete {
+                    self.notices.push(format!("Deleted {} voxels", voxels.len()));
+                    for voxel in voxels {
+                        self.delete_voxel(voxel);
+                    }
+                }
+                else if let Some(replacement) = maybe_replacement {
+                    self.notices.push(format!("Recolored {} voxels", voxels.len()));
+                    for voxel in voxels {
+                        self.place_voxel(voxel, replacement);
+                    }
+                }
+
+                self.history.commit();
+            },
+            None => self.notices.push(format!("More than {} voxels are connected", FloodFill::MAX_VOXELS))
+        }
+    }
+
+    /// Selects the material of the voxel under the pointer, if the user requested it. The engine does not expose
+    /// voxel contents, so only voxels edited through this mod have a known material.
+    fn handle_pick_material(&mut self, hit_result: Option<&RaycastHit>) {
+        let pick_material = self.frame_input.digital(self.user_actions.pick_material);
+
+        if let Some(hit) = hit_result.filter(|x| pick_material.pressed && x.object == (RaycastObject::World { })) {
+            match self.material_cache.get(hit.voxel) {
+                Some(material) => match self.palette.index_of(material) {
+                    Some(item) => self.hotbar.pick(item),
+                    None => self.notices.push(format!("Material {material} is not in the palette"))
+                },
+                None => self.notices.push("The material of the targeted voxel is unknown".to_string())
+            }
+        }
+    }
+
+    /// Updates the player's position based upon user input. On layouts where digits require Shift,
+    /// selecting a hotbar slot does not also sneak. Returns the player's new transform.
     fn move_player(&mut self) -> Transform {
-        let delta_time = self.ctx.get::<dyn FrameTiming>().frame_duration().as_secs_f32();
+        let delta_time = self.frame_input.delta_time();
+        let input = &self.frame_input;
+        let rotating_object = self.physics_gun.held.is_some() && input.digital(self.user_actions.rotate_held_object).held;
+        let look_captured = rotating_object || self.action_wheel.is_open() || self.material_wheel.is_open();
+        let pointer_delta = [input.pointer_delta() + self.touch.look_delta(), Vec2::ZERO][look_captured as usize];
+        let look_vertical = input.analog(self.user_actions.look_vertical);
+        let look_horizontal = input.analog(self.user_actions.look_horizontal);
+        let look_analog = [vec2(look_horizontal, look_vertical), Vec2::ZERO][look_captured as usize];
+        let jump = input.digital(self.user_actions.jump).held || self.touch.button(TouchButton::Up).held;
+        let move_forward = (input.analog(self.user_actions.move_forward) + self.touch.movement().y).clamp(-1.0, 1.0);
+        let move_sideways = (input.analog(self.user_actions.move_sideways) + self.touch.movement().x).clamp(-1.0, 1.0);
+        let selecting_slot = self.layout.shifts_digits() && self.user_actions.hotbar_slots.iter().any(|&x| input.digital(x).held);
+        let sneak = (input.digital(self.user_actions.sneak).held && !selecting_slot) || self.touch.button(TouchButton::Down).held;
+        let toggle_pointer_lock = input.digital(self.user_actions.toggle_pointer_lock);
+
         let mut input = self.ctx.get_mut::<dyn Input>();
-        let pointer_delta = input.pointer_delta();
-        let look_vertical = input.get(self.user_actions.look_vertical);
-        let look_horizontal = input.get(self.user_actions.look_horizontal);
-        let jump = input.get(self.user_actions.jump);
-        let move_forward = input.get(self.user_actions.move_forward);
-        let move_sideways = input.get(self.user_actions.move_sideways);
-        let sneak = input.get(self.user_actions.sneak);
-        let toggle_pointer_lock = input.get(self.user_actions.toggle_pointer_lock);
-
-        let lock_pointer = (input.pointer_locked() ^ toggle_pointer_lock.pressed) || (0.0 < look_vertical.abs().max(look_horizontal.abs()));
+        let lock_pointer = (input.pointer_locked() ^ toggle_pointer_lock.pressed) || (0.0 < look_vertical.abs().max(look_horizontal.abs())) || self.touch.in_use();
         input.set_pointer_locked(lock_pointer);
         
         drop(input);
@@ -158,9 +626,9 @@ impl PlayerController {
         let mut player = self.ctx.get_mut::<dyn Player>();
         let mut transform = player.get_transform();
 
-        Self::update_player_look_direction(&mut transform, pointer_delta, delta_time * vec2(look_horizontal, look_vertical));
+        Self::update_player_look_direction(&mut transform, pointer_delta, delta_time * look_analog);
 
-        let net_vertical_motion = [0.0, 1.0][jump.held as usize] + [0.0, -1.0][sneak.held as usize];
+        let net_vertical_motion = [0.0, 1.0][(jump && !self.navigating_ui) as usize] + [0.0, -1.0][(sneak && !self.navigating_ui) as usize];
         Self::update_player_position(&mut transform, delta_time, vec3a(move_sideways, net_vertical_motion, move_forward));
         
         player.set_transform(transform);
@@ -169,15 +637,151 @@ impl PlayerController {
         transform
     }
 
-    /// Updates the item that the user currently has selected.
+    /// Opens or closes the spawn menu, entity inspector, and key binding menu. The pointer is released when
+    /// either menu opens so that its entries may be clicked, and locked again once both menus have closed.
+    fn update_windows(&mut self) {
+        let toggle_entity_inspector = self.frame_input.digital(self.user_actions.toggle_entity_inspector);
+        let toggle_key_bindings = self.frame_input.digital(self.user_actions.toggle_key_bindings);
+        let toggle_spawn_menu = self.frame_input.digital(self.user_actions.toggle_spawn_menu);
+        let mut input = self.ctx.get_mut::<dyn Input>();
+        let menus_were_open = self.spawn_menu.open || self.rebinding.open;
+        self.spawn_menu.open ^= toggle_spawn_menu.pressed || self.wheel_command == Some(WheelCommand::ToggleSpawnMenu);
+        self.rebinding.open ^= toggle_key_bindings.pressed || self.wheel_command == Some(WheelCommand::ToggleKeyBindings);
+        let menus_open = self.spawn_menu.open || self.rebinding.open;
+
+        if menus_open && !menus_were_open {
+            self.relock_pointer = input.pointer_locked();
+            input.set_pointer_locked(false);
+        }
+        else if !menus_open && std::mem::take(&mut self.relock_pointer) {
+            input.set_pointer_locked(true);
+        }
+
+        self.inspector.open ^= toggle_entity_inspector.pressed || self.wheel_command == Some(WheelCommand::ToggleEntityInspector);
+    }
+
+    /// Switches sculpting mode, operation, and strength according to the player's input.
+    fn update_sculptor(&mut self) {
+        let input = &self.frame_input;
+        let cycle_sculpt_operation = input.digital(self.user_actions.cycle_sculpt_operation);
+        let cycle_sculpt_strength = input.digital(self.user_actions.cycle_sculpt_strength);
+        let toggle_sculpt_mode = input.digital(self.user_actions.toggle_sculpt_mode);
+
+        if toggle_sculpt_mode.pressed || self.wheel_command == Some(WheelCommand::ToggleSculptMode) {
+            self.sculptor.toggle();
+        }
+
+        if cycle_sculpt_operation.pressed || self.wheel_command == Some(WheelCommand::CycleSculptOperation) {
+            self.sculptor.cycle_operation();
+        }
+
+        if cycle_sculpt_strength.pressed || self.wheel_command == Some(WheelCommand::CycleSculptStrength) {
+            self.sculptor.cycle_strength();
+        }
+    }
+
+    /// Updates the radial menus, and moves through egui windows when gamepad navigation buttons are pressed
+    /// while the pointer is unlocked. Navigation takes precedence over the game actions sharing those buttons.
+    fn update_gamepad_controls(&mut self) {
+        let pointer_locked = self.ctx.get::<dyn Input>().pointer_locked();
+        let input = &self.frame_input;
+        let open_action_wheel = input.digital(self.user_actions.open_action_wheel);
+        let open_material_wheel = input.digital(self.user_actions.open_material_wheel);
+        let look = vec2(input.analog(self.user_actions.look_horizontal), input.analog(self.user_actions.look_vertical));
+        let pointer_delta = input.pointer_delta();
+        let menu_activate = input.digital(self.user_actions.menu_activate);
+        let menu_back = input.digital(self.user_actions.menu_back);
+        let menu_directions = [
+            (input.digital(self.user_actions.menu_up), egui::FocusDirection::Up),
+            (input.digital(self.user_actions.menu_down), egui::FocusDirection::Down),
+            (input.digital(self.user_actions.menu_left), egui::FocusDirection::Left),
+            (input.digital(self.user_actions.menu_right), egui::FocusDirection::Right)
+        ];
+
+        self.wheel_command = self.action_wheel.update(open_action_wheel.held, look, pointer_delta, WheelCommand::ALL.len()).map(|x| WheelCommand::ALL[x]);
+
+        let material_count = self.palette.len().min(RadialMenu::MAX_ENTRIES);
+        if let Some(item) = self.material_wheel.update(open_material_wheel.held, look, pointer_delta, material_count) {
+            self.hotbar.pick(item as u32);
+        }
+
+        self.navigating_ui = !pointer_locked && (menu_activate.held || menu_back.held || menu_directions.iter().any(|x| x.0.held));
+        if self.navigating_ui {
+            let direction = menu_directions.iter().find(|x| x.0.pressed).map(|x| x.1);
+            let egui_system = self.ctx.get::<dyn egui::Egui>();
+            navigate_egui(&egui_system.context(), direction, menu_activate.pressed);
+            drop(egui_system);
+
+            if menu_back.pressed {
+                self.ctx.get_mut::<dyn Input>().set_pointer_locked(true);
+            }
+        }
+    }
+
+    /// Updates the hotbar slot and item that the user currently has selected. While the modifier
+    /// is held, the item toggles move between slots instead, so that gamepads may also change slot.
     fn update_selected_item(&mut self) {
-        let input = self.ctx.get::<dyn Input>();
+        let modifier_held = self.update_brush();
+        let input = &self.frame_input;
         let scroll_delta = input.scroll_delta();
-        let toggle_item_left = input.get(self.user_actions.toggle_item_left);
-        let toggle_item_right = input.get(self.user_actions.toggle_item_right);
+        let toggle_item_left = input.digital(self.user_actions.toggle_item_left);
+        let toggle_item_right = input.digital(self.user_actions.toggle_item_right);
+        let selected_slot = self.user_actions.hotbar_slots.iter().position(|&slot| input.digital(slot).pressed);
+
+        let net_toggle_item = [[0, -1][toggle_item_left.pressed as usize] + [0, 1][toggle_item_right.pressed as usize], 0][self.navigating_ui as usize];
+        if modifier_held {
+            self.hotbar.cycle_slot(net_toggle_item);
+        }
+        else {
+            self.hotbar.cycle_item(net_toggle_item, self.palette.len());
+        }
+
+        if !modifier_held && self.physics_gun.held.is_none() {
+            self.hotbar.cycle_slot(-scroll_delta.y);
+        }
+
+        if let Some(slot) = selected_slot {
+            self.hotbar.select_slot(slot);
+        }
+    }
 
-        let net_toggle_item = -scroll_delta.y + [0, -1][toggle_item_left.pressed as usize] + [0, 1][toggle_item_right.pressed as usize];
-        self.selected_item = self.selected_item.wrapping_add(net_toggle_item as u32);
+    /// Reads the input for this frame and passes it through the input recorder, so that
+    /// every later read uses the recorded values while a session is being replayed.
+    /// When a recording or replay begins, the state that input does not reproduce is reset.
+    fn update_frame_input(&mut self) {
+        let delta_time = self.ctx.get::<dyn FrameTiming>().frame_duration().as_secs_f32();
+        let frame = self.frame_input.capture(&*self.ctx.get::<dyn Input>(), delta_time);
+        let mut recorder = self.ctx.get_mut::<dyn InputRecorder>();
+        let replayed = recorder.process_frame("Player controller".to_string(), frame);
+        let session = recorder.session();
+        drop(recorder);
+
+        self.frame_input.set_frame(replayed);
+        if session != self.recorder_session {
+            self.recorder_session = session;
+            self.reset_session_state();
+        }
+    }
+
+    /// Returns the brush, edit history, hotbar, sculptor, held entity, gesture and wheel progress, placement timing,
+    /// and spawn menu choice to their defaults, so that a replay begins from the same state as its recording.
+    /// Palette entries are kept, since they are configuration rather than play state.
+    fn reset_session_state(&mut self) {
+        self.action_wheel = RadialMenu::default();
+        self.brush = Brush::default();
+        self.history.clear();
+        self.hotbar = Hotbar::default();
+        self.interaction = None;
+        self.material_wheel = RadialMenu::default();
+        self.navigating_ui = false;
+        self.physics_gun = PhysicsGun::default();
+        self.redo_chord.reset();
+        self.sculptor = Sculptor::default();
+        self.spawn_menu.reset_selection();
+        self.undo_chord.reset();
+        self.wait_for_placement_until = Duration::ZERO;
+        self.was_placing = false;
+        self.wheel_command = None;
     }
 
     /// Moves the player according to user inputs.
@@ -185,9 +789,16 @@ impl PlayerController {
         /// The maximum distance away that the user may select something.
         const MAX_PLACEMENT_DISTANCE: f32 = 256.0;
 
+        self.update_frame_input();
+        self.touch.update(&self.ctx.get::<dyn egui::Egui>().context());
+        self.update_gamepad_controls();
         self.update_selected_item();
-        let maybe_pointer_direction = self.ctx.get::<dyn Input>().pointer_direction();
+        self.update_sculptor();
+        self.update_windows();
+        self.handle_undo_redo();
+        let maybe_pointer_direction = self.frame_input.pointer_direction();
         let transform = self.move_player();
+        let mut pointer_target = None;
         
         if let Some(direction) = maybe_pointer_direction {
             let pointer_ray = Ray {
@@ -197,12 +808,17 @@ impl PlayerController {
             };
 
             let hit_result = self.ctx.get::<dyn Raycaster>().cast(&pointer_ray);
+            self.resolve_interaction(hit_result.as_ref());
             self.handle_object_interaction(&pointer_ray, hit_result.as_ref());
-            self.handle_player_place_destroy(hit_result.as_ref());
+            self.inspector.observe(&pointer_ray, hit_result.as_ref());
+            self.handle_pick_material(hit_result.as_ref());
+            self.handle_flood_fill(hit_result.as_ref());
+            self.handle_player_place_destroy(&pointer_ray, hit_result.as_ref());
+            pointer_target = Some((pointer_ray, hit_result));
         }
 
         let pointer_locked = self.ctx.get::<dyn Input>().pointer_locked();
-        self.draw_controls_gui(pointer_locked);
+        self.draw_controls_gui(pointer_locked, &transform, pointer_target.as_ref());
     }
 
     /// Draws crosshairs on the center of the screen to help the player aim.
@@ -218,19 +834,41 @@ impl PlayerController {
     fn draw_item_text(painter: &mut egui::Painter, text: &str) {
         let center = painter.clip_rect().center();
         let bottom = painter.clip_rect().bottom();
-        let text_position = egui::pos2(center.x, bottom - 50.0);
+        let text_position = egui::pos2(center.x, bottom - 80.0);
 
         let rect_shape = painter.add(egui::Shape::Noop);
         let text_rect = painter.text(text_position, egui::Align2::CENTER_CENTER, text, egui::FontId::default(), egui::Color32::WHITE);
         painter.set(rect_shape, egui::Shape::rect_filled(text_rect.expand(5.0), 3.0, egui::Color32::from_black_alpha(64)));
     }
 
-    /// Registers the set of actions relevant to player movement.
-    fn get_user_actions(ctx: &mut WingsContextHandle<Self>) -> UserActions {
+    /// Registers the set of actions relevant to player movement, with the player's overrides applied.
+    /// Returns the actions, alongside their bindings so that conflicts may be reported and the actions listed for rebinding.
+    fn get_user_actions(ctx: &mut WingsContextHandle<Self>) -> (UserActions, ActionBindings) {
         let mut input = ctx.get_mut::<dyn Input>();
+        let mut bindings = ActionBindings::new("Player controller");
+        bindings.allow("Drag entity", "Place");
+        bindings.allow("Menu activate", "Jump");
+        bindings.allow("Menu back", "Sneak");
+        bindings.allow("Menu down", "Toggle hollow brush");
+        bindings.allow("Menu left", "Toggle item (left)");
+        bindings.allow("Menu right", "Toggle item (right)");
+        bindings.allow("Menu up", "Cycle brush shape");
 
-        let delete_voxels = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Delete"),
+        let delete_entity = bindings.define_digital::<Self>(
+            &mut *input,
+            "Delete entity",
+            "Removes the held physics entity, or the one under the player's pointer, from play.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Back)
+                },
+            ]
+        );
+
+        let delete_voxels = bindings.define_digital::<Self>(
+            &mut *input,
+            "Delete",
             "Deletes voxels where the player's pointer is.",
             &[
                 DigitalBinding {
@@ -242,10 +880,127 @@ impl PlayerController {
                     raw_input: RawInput::MouseButton(MouseButton::Left)
                 },
             ]
+        );
+
+        let modifier = bindings.define_digital::<Self>(
+            &mut *input,
+            "Modifier",
+            "While held, the scroll wheel and the brush shape and fill buttons change the brush radius, the item buttons change hotbar slot, and the undo and redo keys take effect.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::LeftThumb)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::LControl)
+                },
+            ]
+        );
+
+        let cycle_brush_shape = bindings.define_digital::<Self>(
+            &mut *input,
+            "Cycle brush shape",
+            "Switches between the cube, cylinder, and sphere brushes.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadUp)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::B)
+                },
+            ]
+        );
+
+        let cycle_flood_connectivity = bindings.define_digital::<Self>(
+            &mut *input,
+            "Cycle flood connectivity",
+            "Switches whether flood fills connect voxels through their faces, edges, or corners.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::O)
+                },
+            ]
+        );
+
+        let cycle_sculpt_operation = bindings.define_digital::<Self>(
+            &mut *input,
+            "Cycle sculpt operation",
+            "Switches between raising, lowering, smoothing, and flattening terrain.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::L)
+                },
+            ]
+        );
+
+        let cycle_sculpt_strength = bindings.define_digital::<Self>(
+            &mut *input,
+            "Cycle sculpt strength",
+            "Changes how many voxels each terrain column may move in a single step.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::J)
+                },
+            ]
+        );
+
+        let flood_delete = bindings.define_digital::<Self>(
+            &mut *input,
+            "Flood delete",
+            "Deletes the connected voxels that share the material of the voxel where the player's pointer is.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Delete)
+                },
+            ]
+        );
+
+        let flood_recolor = bindings.define_digital::<Self>(
+            &mut *input,
+            "Flood recolor",
+            "Changes the connected voxels that share the material of the voxel where the player's pointer is into the selected item.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::X)
+                },
+            ]
+        );
+
+        let freeze_held_object = bindings.define_digital::<Self>(
+            &mut *input,
+            "Freeze held object",
+            "Pins the held physics entity in place, or releases the pinned entity if nothing is held.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::P)
+                },
+            ]
+        );
+
+        let hotbar_slots = std::array::from_fn(|slot| bindings.define_digital::<Self>(
+            &mut *input,
+            HOTBAR_SLOT_NAMES[slot],
+            "Selects the hotbar slot with the corresponding number.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(HOTBAR_SLOT_KEYS[slot])
+                },
+            ]
         ));
 
-        let drag_physics_entity = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Drag entity"),
+        let drag_physics_entity = bindings.define_digital::<Self>(
+            &mut *input,
+            "Drag entity",
             "Drags a physics entity around the scene.",
             &[
                 DigitalBinding {
@@ -257,10 +1012,11 @@ impl PlayerController {
                     raw_input: RawInput::MouseButton(MouseButton::Right)
                 },
             ]
-        ));
+        );
 
-        let look_vertical = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Look vertical"),
+        let look_vertical = bindings.define_analog::<Self>(
+            &mut *input,
+            "Look vertical",
             "Causes the player to look up or down.",
             &[
                 AnalogBinding {
@@ -268,10 +1024,11 @@ impl PlayerController {
                     raw_input: RawInput::GamepadAxis(GamepadAxis::RightStickY)
                 },
             ]
-        ));
+        );
 
-        let look_horizontal = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Look horizontal"),
+        let look_horizontal = bindings.define_analog::<Self>(
+            &mut *input,
+            "Look horizontal",
             "Causes the player to look left or right.",
             &[
                 AnalogBinding {
@@ -279,10 +1036,11 @@ impl PlayerController {
                     raw_input: RawInput::GamepadAxis(GamepadAxis::RightStickX)
                 },
             ]
-        ));
+        );
 
-        let jump = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Jump"),
+        let jump = bindings.define_digital::<Self>(
+            &mut *input,
+            "Jump",
             "Causes the player to jump or move upward.",
             &[
                 DigitalBinding {
@@ -294,10 +1052,83 @@ impl PlayerController {
                     raw_input: RawInput::Key(Key::Space)
                 },
             ]
-        ));
+        );
+
+        let menu_activate = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu activate",
+            "Presses the focused menu widget when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::South)
+                },
+            ]
+        );
 
-        let move_forward = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Move forward"),
+        let menu_back = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu back",
+            "Leaves menu navigation and locks the pointer again when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::East)
+                },
+            ]
+        );
+
+        let menu_down = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu down",
+            "Moves focus to the menu widget below when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadDown)
+                },
+            ]
+        );
+
+        let menu_left = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu left",
+            "Moves focus to the menu widget to the left when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadLeft)
+                },
+            ]
+        );
+
+        let menu_right = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu right",
+            "Moves focus to the menu widget to the right when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadRight)
+                },
+            ]
+        );
+
+        let menu_up = bindings.define_digital::<Self>(
+            &mut *input,
+            "Menu up",
+            "Moves focus to the menu widget above when using a gamepad.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadUp)
+                },
+            ]
+        );
+
+        let move_forward = bindings.define_analog::<Self>(
+            &mut *input,
+            "Move forward",
             "Causes the player to walk forward or backward.",
             &[
                 AnalogBinding {
@@ -313,10 +1144,11 @@ impl PlayerController {
                     raw_input: RawInput::GamepadAxis(GamepadAxis::LeftStickY)
                 },
             ]
-        ));
+        );
 
-        let move_sideways = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Move sideways"),
+        let move_sideways = bindings.define_analog::<Self>(
+            &mut *input,
+            "Move sideways",
             "Causes the player to walk left or right.",
             &[
                 AnalogBinding {
@@ -332,10 +1164,55 @@ impl PlayerController {
                     raw_input: RawInput::GamepadAxis(GamepadAxis::LeftStickX)
                 },
             ]
-        ));
+        );
+
+        let open_action_wheel = bindings.define_digital::<Self>(
+            &mut *input,
+            "Open action wheel",
+            "While held, shows a wheel of less common commands, one of which is chosen by aiming and releasing.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::Start)
+                },
+            ]
+        );
+
+        let open_material_wheel = bindings.define_digital::<Self>(
+            &mut *input,
+            "Open material wheel",
+            "While held, shows a wheel of palette items, one of which is selected by aiming and releasing.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::RightThumb)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Tab)
+                },
+            ]
+        );
 
-        let place_voxels = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Place"),
+        let pick_material = bindings.define_digital::<Self>(
+            &mut *input,
+            "Pick material",
+            "Selects the material of the voxel where the player's pointer is, if it was edited through this mod.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::West)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::MouseButton(MouseButton::Middle)
+                },
+            ]
+        );
+
+        let place_voxels = bindings.define_digital::<Self>(
+            &mut *input,
+            "Place",
             "Places voxels where the player's pointer is.",
             &[
                 DigitalBinding {
@@ -347,10 +1224,39 @@ impl PlayerController {
                     raw_input: RawInput::MouseButton(MouseButton::Right)
                 },
             ]
-        ));
+        );
+
+        let redo = bindings.define_digital::<Self>(
+            &mut *input,
+            "Redo",
+            "Reapplies the most recently undone edit, while the modifier is held.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Y)
+                },
+            ]
+        );
+
+        let rotate_held_object = bindings.define_digital::<Self>(
+            &mut *input,
+            "Rotate held object",
+            "While held, moving the pointer turns the held physics entity instead of the camera.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::RightTrigger2)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::LAlt)
+                },
+            ]
+        );
 
-        let sneak = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Sneak"),
+        let sneak = bindings.define_digital::<Self>(
+            &mut *input,
+            "Sneak",
             "Causes the player to sneak or move downward.",
             &[
                 DigitalBinding {
@@ -362,11 +1268,12 @@ impl PlayerController {
                     raw_input: RawInput::Key(Key::LShift)
                 },
             ]
-        ));
+        );
 
-        let spawn_physics_entity = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Spawn debug entity"),
-            "Spawns a physics entity for debugging.",
+        let spawn_physics_entity = bindings.define_digital::<Self>(
+            &mut *input,
+            "Spawn debug entity",
+            "Spawns the physics entity chosen in the spawn menu in front of the player.",
             &[
                 DigitalBinding {
                     threshold: 0.9,
@@ -377,22 +1284,68 @@ impl PlayerController {
                     raw_input: RawInput::Key(Key::G)
                 },
             ]
-        ));
+        );
 
-        let toggle_item_left = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Toggle item (left)"),
-            "Toggles the selected item to the left.",
+        let throw_held_object = bindings.define_digital::<Self>(
+            &mut *input,
+            "Throw held object",
+            "Releases the held physics entity, sending it along the pointer with the camera's velocity.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::LeftTrigger2)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::U)
+                },
+            ]
+        );
+
+        let toggle_brush_hollow = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle hollow brush",
+            "Switches the brush between affecting its whole volume and only its surface.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::DPadDown)
+                },
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::H)
+                },
+            ]
+        );
+
+        let toggle_entity_inspector = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle entity inspector",
+            "Shows or hides information about the physics entity under the pointer.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::F3)
+                },
+            ]
+        );
+
+        let toggle_item_left = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle item (left)",
+            "Changes the item in the selected hotbar slot to the previous palette entry.",
             &[
                 DigitalBinding {
                     threshold: 0.9,
                     raw_input: RawInput::GamepadButton(GamepadButton::DPadLeft)
                 },
             ]
-        ));
+        );
 
-        let toggle_item_right = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Toggle item (right)"),
-            "Toggles the selected item to the right.",
+        let toggle_item_right = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle item (right)",
+            "Changes the item in the selected hotbar slot to the next palette entry.",
             &[
                 DigitalBinding {
                     threshold: 0.9,
@@ -403,34 +1356,117 @@ impl PlayerController {
                     raw_input: RawInput::GamepadButton(GamepadButton::DPadRight)
                 },
             ]
-        ));
+        );
+
+        let toggle_key_bindings = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle key bindings",
+            "Opens or closes the menu for binding actions to different inputs.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::F1)
+                },
+            ]
+        );
 
-        let toggle_pointer_lock = input.define(ActionDescriptor::new(
-            ActionName::new::<Self>("Toggle pointer lock"),
+        let toggle_pointer_lock = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle pointer lock",
             "Toggles whether the mouse should be locked to the center of the screen.",
             &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::GamepadButton(GamepadButton::Select)
+                },
                 DigitalBinding {
                     threshold: 0.9,
                     raw_input: RawInput::Key(Key::T)
                 }
             ]
-        ));
+        );
         
-        UserActions {
+        let toggle_sculpt_mode = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle sculpt mode",
+            "Switches between placing voxels and sculpting the terrain around the pointer.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::K)
+                },
+            ]
+        );
+
+        let toggle_spawn_menu = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle spawn menu",
+            "Opens or closes the menu for choosing which physics entity to spawn.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::I)
+                },
+            ]
+        );
+
+        let undo = bindings.define_digital::<Self>(
+            &mut *input,
+            "Undo",
+            "Reverts the most recent edit, while the modifier is held.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Z)
+                },
+            ]
+        );
+
+        let actions = UserActions {
+            cycle_brush_shape,
+            cycle_flood_connectivity,
+            cycle_sculpt_operation,
+            cycle_sculpt_strength,
             drag_physics_entity,
+            delete_entity,
             delete_voxels,
+            flood_delete,
+            flood_recolor,
+            freeze_held_object,
+            hotbar_slots,
             look_horizontal,
             look_vertical,
             jump,
+            menu_activate,
+            menu_back,
+            menu_down,
+            menu_left,
+            menu_right,
+            menu_up,
+            modifier,
             move_forward,
             move_sideways,
+            open_action_wheel,
+            open_material_wheel,
+            pick_material,
             place_voxels,
+            redo,
+            rotate_held_object,
             sneak,
             spawn_physics_entity,
+            throw_held_object,
+            toggle_brush_hollow,
+            toggle_entity_inspector,
             toggle_item_left,
             toggle_item_right,
-            toggle_pointer_lock
-        }
+            toggle_key_bindings,
+            toggle_pointer_lock,
+            toggle_sculpt_mode,
+            toggle_spawn_menu,
+            undo
+        };
+
+        (actions, bindings)
     }
 
     /// Updates the direction that the player is looking based the user input deltas.
@@ -470,6 +1506,7 @@ impl WingsSystem for PlayerController {
         .with::<dyn egui::Egui>()
         .with::<dyn FrameTiming>()
         .with::<dyn Input>()
+        .with::<dyn InputRecorder>()
         .with::<dyn Player>()
         .with::<dyn Raycaster>();
 
@@ -477,63 +1514,247 @@ impl WingsSystem for PlayerController {
         .with(Self::handle_player_input);
 
     fn new(mut ctx: WingsContextHandle<Self>) -> Self {
-        let dragged_object = None;
-        let object_kind = 0;
-        let selected_item = 0;
+        let action_wheel = RadialMenu::default();
+        let brush = Brush::default();
+        let flood_fill = FloodFill::default();
+        let history = EditHistory::default();
+        let material_wheel = RadialMenu::default();
+        let navigating_ui = false;
+        let (palette, palette_error) = Palette::load();
+        let (spawn_menu, spawn_menu_error) = SpawnMenu::load();
+        let mut notices = NoticeBoard::default();
+        notices.extend(palette_error.into_iter().chain(spawn_menu_error));
+        let physics_gun = PhysicsGun::default();
+        let projection = ScreenProjection::load();
+        let sculptor = Sculptor::default();
+        let touch = TouchControls::default();
+        let hotbar = Hotbar::default();
+        let inspector = EntityInspector::default();
         let wait_for_placement_until = Duration::ZERO;
         let was_placing = false;
-        let user_actions = Self::get_user_actions(&mut ctx);
+        let wheel_command = None;
+        let interaction = None;
+        let rebinding = RebindingMenu::load(GamepadCapture::new::<Self>(&mut *ctx.get_mut::<dyn Input>()));
+        let recorder_session = 0;
+        let (user_actions, bindings) = Self::get_user_actions(&mut ctx);
+        let frame_input = bindings.snapshot();
+        let redo_chord = Gesture::press(user_actions.redo).with_modifiers(&[user_actions.modifier]);
+        let undo_chord = Gesture::press(user_actions.undo).with_modifiers(&[user_actions.modifier]);
+        let layout = bindings.layout();
+        let material_cache = MaterialCache::default();
+        let registered_actions = bindings.actions().into_iter().chain(ctx.get::<dyn InputRecorder>().actions()).collect();
+        let relock_pointer = false;
+        notices.extend(bindings.warnings());
         
         Self {
+            action_wheel,
+            brush,
             ctx,
-            dragged_object,
-            object_kind,
-            selected_item,
+            flood_fill,
+            frame_input,
+            history,
+            hotbar,
+            inspector,
+            interaction,
+            layout,
+            material_cache,
+            material_wheel,
+            navigating_ui,
+            notices,
+            palette,
+            physics_gun,
+            projection,
+            rebinding,
+            recorder_session,
+            redo_chord,
+            registered_actions,
+            relock_pointer,
+            sculptor,
+            spawn_menu,
+            touch,
+            undo_chord,
             user_actions,
             wait_for_placement_until,
-            was_placing
+            was_placing,
+            wheel_command
         }
     }
 }
 
-/// Stores information about an object being dragged.
-#[derive(Copy, Clone, Debug)]
-struct DraggedObject {
-    /// The body-local point at which the object was grabbed.
-    pub contact_point: Vec3A,
-    /// The ID of the entity being dragged.
-    pub id: u64,
-    /// The distance that the entity is held away from the player.
-    pub distance: f32
+impl ItemPalette for PlayerController {
+    fn add_entry(&mut self, entry: PaletteEntry) {
+        self.palette.add(entry);
+    }
+
+    fn entries(&self) -> Vec<PaletteEntry> {
+        self.palette.entries().to_vec()
+    }
+
+    fn selected_entry(&self) -> Option<PaletteEntry> {
+        self.palette.get(self.hotbar.selected_item()).cloned()
+    }
+}
+
+impl ActionRegistry for PlayerController {
+    fn register_actions(&mut self, actions: Vec<BoundAction>) {
+        self.registered_actions.retain(|x| !actions.iter().any(|y| x.group == y.group && x.name == y.name));
+        self.registered_actions.extend(actions);
+    }
+
+    fn actions(&self) -> Vec<BoundAction> {
+        self.registered_actions.clone()
+    }
+}
+
+impl ObjectCatalog for PlayerController {
+    fn add_object(&mut self, object: SpawnableObject) {
+        self.spawn_menu.add(object);
+    }
+
+    fn objects(&self) -> Vec<SpawnableObject> {
+        self.spawn_menu.objects().to_vec()
+    }
+}
+
+/// The distance around the player within which voxels may not be placed.
+const PLAYER_CLEARANCE: f32 = 0.5;
+
+/// The names of the actions that select each hotbar slot.
+const HOTBAR_SLOT_NAMES: [&str; Hotbar::SLOT_COUNT] = [
+    "Hotbar slot 1", "Hotbar slot 2", "Hotbar slot 3",
+    "Hotbar slot 4", "Hotbar slot 5", "Hotbar slot 6",
+    "Hotbar slot 7", "Hotbar slot 8", "Hotbar slot 9"
+];
+
+/// The default keys that select each hotbar slot.
+const HOTBAR_SLOT_KEYS: [Key; Hotbar::SLOT_COUNT] = [
+    Key::Key1, Key::Key2, Key::Key3,
+    Key::Key4, Key::Key5, Key::Key6,
+    Key::Key7, Key::Key8, Key::Key9
+];
+
+impl VoxelEditor for PlayerController {
+    fn delete_voxels(&mut self, voxels: Vec<IVec3>) {
+        self.history.commit();
+        for voxel in voxels {
+            self.delete_voxel(voxel);
+        }
+        self.history.commit();
+    }
+
+    fn materials_at(&self, voxels: Vec<IVec3>) -> Vec<Option<u32>> {
+        voxels.into_iter().map(|x| self.material_cache.get(x)).collect()
+    }
+
+    fn place_materials(&mut self, voxels: Vec<(IVec3, u32)>) {
+        self.history.commit();
+        for (voxel, material) in voxels {
+            self.place_voxel(voxel, material);
+        }
+        self.history.commit();
+    }
+
+    fn place_voxels(&mut self, voxels: Vec<IVec3>, material: u32) {
+        self.history.commit();
+        for voxel in voxels {
+            self.place_voxel(voxel, material);
+        }
+        self.history.commit();
+    }
+}
+
+/// An action chosen for an input that is shared between several actions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Interaction {
+    /// The input drags a physics entity.
+    DragEntity,
+    /// The input places voxels.
+    PlaceVoxels
 }
 
 /// Holds the set of actions relevant to user input.
 #[derive(Copy, Clone, Debug)]
 struct UserActions {
+    /// Switches between the available brush shapes.
+    pub cycle_brush_shape: ActionId<Digital>,
+    /// Switches which neighbors flood fills connect.
+    pub cycle_flood_connectivity: ActionId<Digital>,
+    /// Switches between the available sculpting operations.
+    pub cycle_sculpt_operation: ActionId<Digital>,
+    /// Changes how many voxels each terrain column may move in a single step.
+    pub cycle_sculpt_strength: ActionId<Digital>,
+    /// Removes the held physics entity, or the one under the pointer, from play.
+    pub delete_entity: ActionId<Digital>,
     /// Deletes voxels where the player's pointer is.
     pub delete_voxels: ActionId<Digital>,
     /// Drags a physics entity around the screen.
     pub drag_physics_entity: ActionId<Digital>,
+    /// Deletes the connected voxels that share the targeted material.
+    pub flood_delete: ActionId<Digital>,
+    /// Recolors the connected voxels that share the targeted material.
+    pub flood_recolor: ActionId<Digital>,
+    /// Pins the held physics entity in place.
+    pub freeze_held_object: ActionId<Digital>,
+    /// Selects each of the hotbar slots directly.
+    pub hotbar_slots: [ActionId<Digital>; Hotbar::SLOT_COUNT],
     /// Causes the player to look left or right.
     pub look_horizontal: ActionId<Analog>,
     /// Causes the player to look up or down.
     pub look_vertical: ActionId<Analog>,
     /// Causes the player to move upward.
     pub jump: ActionId<Digital>,
+    /// Presses the focused menu widget.
+    pub menu_activate: ActionId<Digital>,
+    /// Leaves menu navigation.
+    pub menu_back: ActionId<Digital>,
+    /// Moves menu focus downward.
+    pub menu_down: ActionId<Digital>,
+    /// Moves menu focus to the left.
+    pub menu_left: ActionId<Digital>,
+    /// Moves menu focus to the right.
+    pub menu_right: ActionId<Digital>,
+    /// Moves menu focus upward.
+    pub menu_up: ActionId<Digital>,
+    /// While held, the scroll wheel changes the brush radius, and the undo and redo keys take effect.
+    pub modifier: ActionId<Digital>,
     /// Causes the player to walk forward or backward.
     pub move_forward: ActionId<Analog>,
     /// Causes the player to walk left or right.
     pub move_sideways: ActionId<Analog>,
+    /// Shows the wheel of less common commands while held.
+    pub open_action_wheel: ActionId<Digital>,
+    /// Shows the wheel of palette items while held.
+    pub open_material_wheel: ActionId<Digital>,
+    /// Selects the material of the voxel where the player's pointer is.
+    pub pick_material: ActionId<Digital>,
     /// Places voxels where the player's pointer is.
     pub place_voxels: ActionId<Digital>,
+    /// Reapplies the most recently undone edit.
+    pub redo: ActionId<Digital>,
+    /// Turns the held physics entity with the pointer.
+    pub rotate_held_object: ActionId<Digital>,
     /// Causes the player to move downward.
     pub sneak: ActionId<Digital>,
-    /// Spawns a physics entity for debugging.
+    /// Spawns the physics entity chosen in the spawn menu.
     pub spawn_physics_entity: ActionId<Digital>,
-    /// Toggles the selected item to the left.
+    /// Releases the held physics entity with the camera's velocity.
+    pub throw_held_object: ActionId<Digital>,
+    /// Switches the brush between affecting its whole volume and only its surface.
+    pub toggle_brush_hollow: ActionId<Digital>,
+    /// Shows or hides the entity inspector.
+    pub toggle_entity_inspector: ActionId<Digital>,
+    /// Changes the item in the selected hotbar slot to the previous palette entry.
     pub toggle_item_left: ActionId<Digital>,
-    /// Toggles the selected item to the right.
+    /// Changes the item in the selected hotbar slot to the next palette entry.
     pub toggle_item_right: ActionId<Digital>,
+    /// Opens or closes the key binding menu.
+    pub toggle_key_bindings: ActionId<Digital>,
     /// Toggles whether the mouse should be locked to the center of the screen.
-    pub toggle_pointer_lock: ActionId<Digital>
+    pub toggle_pointer_lock: ActionId<Digital>,
+    /// Switches between placing voxels and sculpting terrain.
+    pub toggle_sculpt_mode: ActionId<Digital>,
+    /// Opens or closes the spawn menu.
+    pub toggle_spawn_menu: ActionId<Digital>,
+    /// Reverts the most recent edit.
+    pub undo: ActionId<Digital>
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::history::*;
+use std::collections::*;
+use voxel_engine::math::*;
+
+/// Remembers the contents of voxels that were edited through this mod, whether by the player
+/// or by other mods through the `VoxelEditor` system. The engine does not expose voxel contents
+/// to mods, so this is the only way of determining what a voxel is made of.
+#[derive(Clone, Debug, Default)]
+pub struct MaterialCache {
+    /// The material of each voxel, or `None` if it was emptied, keyed by world-space voxel coordinate.
+    materials: HashMap<IVec3, Option<u32>>
+}
+
+impl MaterialCache {
+    /// Gets the material of the given voxel, if it is known to be filled.
+    pub fn get(&self, voxel: IVec3) -> Option<u32> {
+        self.materials.get(&voxel).copied().flatten()
+    }
+
+    /// Gets the contents of the given voxel, if it was edited through this mod.
+    pub fn state(&self, voxel: IVec3) -> Option<VoxelState> {
+        self.materials.get(&voxel).map(|x| x.map_or(VoxelState::Empty, VoxelState::Filled))
+    }
+
+    /// Records that a voxel was filled with the given material.
+    pub fn record_placed(&mut self, voxel: IVec3, material: u32) {
+        self.materials.insert(voxel, Some(material));
+    }
+
+    /// Records that a voxel was emptied.
+    pub fn record_deleted(&mut self, voxel: IVec3) {
+        self.materials.insert(voxel, None);
+    }
+}
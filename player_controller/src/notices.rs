@@ -0,0 +1,87 @@
+/// Messages shown in the top-left corner of the screen, each of which disappears after a while.
+#[derive(Clone, Debug, Default)]
+pub struct NoticeBoard {
+    /// Each message, alongside the number of seconds for which it remains shown.
+    notices: Vec<(String, f32)>
+}
+
+impl NoticeBoard {
+    /// The number of seconds for which each message is shown.
+    pub const DURATION: f32 = 15.0;
+    /// The number of seconds over which a message fades out before it disappears.
+    const FADE_DURATION: f32 = 1.0;
+
+    /// Shows a message for the full duration. If the message is already shown, it is shown for the full duration again.
+    pub fn push(&mut self, notice: String) {
+        self.notices.retain(|(x, _)| *x != notice);
+        self.notices.push((notice, Self::DURATION));
+    }
+
+    /// Advances the time for which the messages have been shown, and removes those which have expired.
+    pub fn update(&mut self, delta_time: f32) {
+        for (_, remaining) in &mut self.notices {
+            *remaining -= delta_time;
+        }
+
+        self.notices.retain(|&(_, remaining)| 0.0 < remaining);
+    }
+
+    /// Gets the messages that are currently shown.
+    #[cfg(test)]
+    pub fn notices(&self) -> impl '_ + Iterator<Item = &str> {
+        self.notices.iter().map(|(x, _)| &x[..])
+    }
+
+    /// Draws the messages, fading out those which are about to expire.
+    pub fn draw(&self, painter: &mut egui::Painter) {
+        let top_left = painter.clip_rect().left_top() + egui::vec2(10.0, 10.0);
+
+        for (index, (notice, remaining)) in self.notices.iter().enumerate() {
+            let opacity = (remaining / Self::FADE_DURATION).min(1.0);
+            let text_position = top_left + egui::vec2(0.0, 20.0 * index as f32);
+            let rect_shape = painter.add(egui::Shape::Noop);
+            let text_rect = painter.text(text_position, egui::Align2::LEFT_TOP, notice, egui::FontId::default(), egui::Color32::YELLOW.gamma_multiply(opacity));
+            painter.set(rect_shape, egui::Shape::rect_filled(text_rect.expand(3.0), 3.0, egui::Color32::from_black_alpha((128.0 * opacity) as u8)));
+        }
+    }
+}
+
+impl Extend<String> for NoticeBoard {
+    fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
+        for notice in iter {
+            self.push(notice);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_expire_after_their_duration() {
+        let mut board = NoticeBoard::default();
+        board.push("first".to_string());
+        board.update(0.5 * NoticeBoard::DURATION);
+        board.push("second".to_string());
+        assert_eq!(board.notices().collect::<Vec<_>>(), ["first", "second"]);
+
+        board.update(0.6 * NoticeBoard::DURATION);
+        assert_eq!(board.notices().collect::<Vec<_>>(), ["second"]);
+
+        board.update(0.6 * NoticeBoard::DURATION);
+        assert_eq!(board.notices().count(), 0);
+    }
+
+    #[test]
+    fn repeated_notices_are_shown_once_for_the_full_duration() {
+        let mut board = NoticeBoard::default();
+        board.extend(["layout".to_string(), "error".to_string()]);
+        board.update(0.9 * NoticeBoard::DURATION);
+        board.push("layout".to_string());
+        assert_eq!(board.notices().collect::<Vec<_>>(), ["error", "layout"]);
+
+        board.update(0.2 * NoticeBoard::DURATION);
+        assert_eq!(board.notices().collect::<Vec<_>>(), ["layout"]);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::*;
+use voxel_std_types::palette::*;
+
+/// Holds the ordered set of items that the player may place.
+#[derive(Clone, Debug)]
+pub struct Palette {
+    /// The entries in the palette, in display order.
+    entries: Vec<PaletteEntry>
+}
+
+impl Palette {
+    /// The path of the file from which palette entries are loaded.
+    const CONFIG_PATH: &'static str = "config/palette.toml";
+
+    /// Loads the palette from the configuration file. If no file exists,
+    /// the default palette is returned. If the file is malformed, the default
+    /// palette is returned alongside a description of the problem.
+    pub fn load() -> (Self, Option<String>) {
+        match std::fs::read_to_string(Self::CONFIG_PATH) {
+            Ok(text) => match toml::from_str::<PaletteConfig>(&text) {
+                Ok(config) if !config.entry.is_empty() => (Self { entries: config.entry }, None),
+                Ok(_) => (Self::default(), Some(format!("{} contains no entries", Self::CONFIG_PATH))),
+                Err(error) => (Self::default(), Some(format!("Failed to parse {}: {error}", Self::CONFIG_PATH)))
+            },
+            Err(_) => (Self::default(), None)
+        }
+    }
+
+    /// Adds an entry to the palette, replacing any entry with the same material.
+    pub fn add(&mut self, entry: PaletteEntry) {
+        if let Some(existing) = self.entries.iter_mut().find(|x| x.material == entry.material) {
+            *existing = entry;
+        }
+        else {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Gets the entry at the given index, if the palette has that many entries.
+    pub fn get(&self, index: u32) -> Option<&PaletteEntry> {
+        self.entries.get(index as usize)
+    }
+
+    /// Gets the index of the first entry that places the given material.
+    pub fn index_of(&self, material: u32) -> Option<u32> {
+        self.entries.iter().position(|x| x.material == material).map(|x| x as u32)
+    }
+
+    /// Gets all entries in the palette.
+    pub fn entries(&self) -> &[PaletteEntry] {
+        &self.entries
+    }
+
+    /// Gets the number of entries in the palette.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Creates a palette entry for one of the builtin materials.
+    fn builtin(name: &str, color: [u8; 4], material: u32) -> PaletteEntry {
+        PaletteEntry {
+            color,
+            icon: None,
+            material,
+            name: name.to_string()
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            entries: vec![
+                Self::builtin("Red light", [255, 64, 64, 255], 0),
+                Self::builtin("Green light", [64, 255, 64, 255], 1),
+                Self::builtin("Blue light", [64, 64, 255, 255], 2),
+                Self::builtin("White light", [255, 255, 255, 255], 3),
+                Self::builtin("Red plastic", [180, 30, 30, 255], 4),
+                Self::builtin("Green plastic", [30, 180, 30, 255], 5),
+                Self::builtin("Blue plastic", [30, 30, 180, 255], 6),
+                Self::builtin("White plastic", [200, 200, 200, 255], 7)
+            ]
+        }
+    }
+}
+
+/// The on-disk representation of a palette.
+#[derive(Clone, Debug, Deserialize)]
+struct PaletteConfig {
+    /// The entries of the palette, in display order.
+    #[serde(default)]
+    entry: Vec<PaletteEntry>
+}
@@ -0,0 +1,314 @@
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+use voxel_engine::player::*;
+use voxel_std_types::targeting::*;
+
+/// Holds, pins, and throws physics entities. The engine only allows a single entity to be
+/// pulled toward a target by one of its points, so every manipulation is expressed as a drag.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PhysicsGun {
+    /// An entity that remains pulled toward a fixed position after being released.
+    pub frozen: Option<FrozenObject>,
+    /// The entity currently held in front of the player.
+    pub held: Option<HeldObject>,
+    /// The voxel-space position of the camera during the previous frame.
+    previous_eye: Option<Vec3A>,
+    /// An entity that was recently thrown, and is still being accelerated.
+    thrown: Option<ThrownObject>,
+    /// The velocity of the camera, in voxels per second.
+    velocity: Vec3A,
+    /// Whether an entity was dragged during the previous frame.
+    was_dragging: bool
+}
+
+impl PhysicsGun {
+    /// How far below its position a deleted entity is dragged, in voxels.
+    const DISCARD_DEPTH: f32 = 4096.0;
+    /// How long a deleted entity is dragged downward, in seconds.
+    const DISCARD_DURATION: f32 = 2.0;
+    /// The closest that a held entity may be to the player.
+    const MIN_HOLD_DISTANCE: f32 = 2.0;
+    /// The furthest that a held entity may be from the player.
+    const MAX_HOLD_DISTANCE: f32 = 200.0;
+    /// The distance that a held entity moves for each step of the scroll wheel.
+    const PUSH_PULL_STEP: f32 = 2.0;
+    /// The angle that a held entity turns for each point of pointer movement.
+    const ROTATION_SPEED: f32 = 0.005;
+    /// The speed at which thrown entities leave the player, in voxels per second.
+    const THROW_SPEED: f32 = 40.0;
+    /// How long a thrown entity continues to be pulled along its path, so that it gains the throw velocity.
+    const THROW_DURATION: f32 = 0.1;
+
+    /// Records the camera position for this frame, so that the camera velocity is known when throwing.
+    pub fn update_velocity(&mut self, eye: Vec3A, delta_time: f32) {
+        if let Some(previous) = self.previous_eye.filter(|_| 0.0 < delta_time) {
+            self.velocity = (eye - previous) / delta_time;
+        }
+
+        self.previous_eye = Some(eye);
+    }
+
+    /// Begins holding the given entity, releasing it first if it was frozen.
+    pub fn grab(&mut self, held: HeldObject) {
+        if self.frozen.is_some_and(|x| x.id == held.id) {
+            self.frozen = None;
+        }
+
+        self.held = Some(held);
+    }
+
+    /// Moves the held entity toward or away from the player by the given number of scroll wheel steps,
+    /// which may be fractional when they come from a pinch.
+    pub fn push_pull(&mut self, steps: f32) {
+        if let Some(held) = &mut self.held {
+            held.distance = (held.distance + Self::PUSH_PULL_STEP * steps).clamp(Self::MIN_HOLD_DISTANCE, Self::MAX_HOLD_DISTANCE);
+        }
+    }
+
+    /// Turns the held entity according to pointer movement. Since the engine cannot set an entity's
+    /// rotation directly, the body-local contact point is rotated around the entity's origin instead,
+    /// and the drag then turns the entity to bring the new contact point toward the target.
+    pub fn rotate(&mut self, pointer_delta: Vec2) {
+        if let Some(held) = &mut self.held {
+            let rotation = Quat::from_euler(EulerRot::YXZ, Self::ROTATION_SPEED * pointer_delta.x, Self::ROTATION_SPEED * pointer_delta.y, 0.0);
+            held.contact_point = rotation * held.contact_point;
+        }
+    }
+
+    /// Pins the held entity where it is, or releases the frozen entity if nothing is held.
+    pub fn toggle_freeze(&mut self, pointer_ray: &Ray) {
+        match self.held.take() {
+            Some(held) => self.frozen = Some(FrozenObject {
+                contact_point: held.contact_point,
+                id: held.id,
+                target_position: held.target_position(pointer_ray)
+            }),
+            None => self.frozen = None
+        }
+    }
+
+    /// Pins an entity that is not held, replacing any entity that was already frozen.
+    pub fn freeze(&mut self, frozen: FrozenObject) {
+        if self.held.is_some_and(|x| x.id == frozen.id) {
+            self.held = None;
+        }
+
+        self.frozen = Some(frozen);
+    }
+
+    /// Releases the frozen entity.
+    pub fn unfreeze(&mut self) {
+        self.frozen = None;
+    }
+
+    /// Releases the held entity, sending it along the pointer with the camera's velocity.
+    pub fn throw(&mut self, pointer_ray: &Ray) {
+        if let Some(held) = self.held.take() {
+            self.thrown = Some(ThrownObject {
+                contact_point: held.contact_point,
+                duration: Self::THROW_DURATION,
+                elapsed: 0.0,
+                id: held.id,
+                origin: to_voxel_space(held.target_position(pointer_ray)),
+                velocity: self.velocity + Self::THROW_SPEED * pointer_ray.direction
+            });
+        }
+    }
+
+    /// Removes an entity from play. Since the engine cannot delete entities, the entity is released if it was held
+    /// or frozen, and then dragged by the given body-local point far below the given voxel-space position.
+    pub fn discard(&mut self, id: u64, contact_point: Vec3A, position: Vec3A) {
+        if self.held.is_some_and(|x| x.id == id) {
+            self.held = None;
+        }
+
+        if self.frozen.is_some_and(|x| x.id == id) {
+            self.frozen = None;
+        }
+
+        self.thrown = Some(ThrownObject {
+            contact_point,
+            duration: Self::DISCARD_DURATION,
+            elapsed: 0.0,
+            id,
+            origin: position,
+            velocity: Self::DISCARD_DEPTH / Self::DISCARD_DURATION * Vec3A::NEG_Y
+        });
+    }
+
+    /// Releases the held entity without throwing it.
+    pub fn release(&mut self) {
+        self.held = None;
+    }
+
+    /// Advances any thrown entity, and gets the drag that should be applied this frame.
+    /// Returns `None` when no drag should be sent to the engine, and `Some(None)` when a previous drag should end.
+    pub fn update_drag(&mut self, pointer_ray: &Ray, delta_time: f32) -> Option<Option<DragEntity>> {
+        if let Some(thrown) = &mut self.thrown {
+            thrown.elapsed += delta_time;
+            if thrown.duration <= thrown.elapsed {
+                self.thrown = None;
+            }
+        }
+
+        let drag = if let Some(held) = self.held {
+            Some(DragEntity { contact_point: held.contact_point, id: held.id, target_position: held.target_position(pointer_ray) })
+        }
+        else if let Some(thrown) = self.thrown {
+            Some(DragEntity { contact_point: thrown.contact_point, id: thrown.id, target_position: (thrown.origin + thrown.elapsed * thrown.velocity).into() })
+        }
+        else {
+            self.frozen.map(|frozen| DragEntity { contact_point: frozen.contact_point, id: frozen.id, target_position: frozen.target_position })
+        };
+
+        let was_dragging = std::mem::replace(&mut self.was_dragging, drag.is_some());
+        (drag.is_some() || was_dragging).then_some(drag)
+    }
+}
+
+/// An entity that remains pulled toward a fixed position.
+#[derive(Copy, Clone, Debug)]
+pub struct FrozenObject {
+    /// The body-local point by which the entity is pulled.
+    pub contact_point: Vec3A,
+    /// The ID of the entity.
+    pub id: u64,
+    /// The position toward which the entity is pulled.
+    pub target_position: WorldVec
+}
+
+/// An entity held in front of the player.
+#[derive(Copy, Clone, Debug)]
+pub struct HeldObject {
+    /// The body-local point at which the object was grabbed.
+    pub contact_point: Vec3A,
+    /// The distance that the entity is held away from the player.
+    pub distance: f32,
+    /// The ID of the entity being held.
+    pub id: u64
+}
+
+impl HeldObject {
+    /// Begins holding an entity by the point at which the pointer struck it, so that the point stays beneath the crosshair.
+    /// The hit point is found from the hit distance along the pointer. The engine reports the struck voxel in body-local
+    /// coordinates but not the pose of the entity, so the hit point is carried into body-local space by its offset within
+    /// the voxel grid. This is exact for entities aligned with the grid, and otherwise remains on the struck voxel.
+    pub fn from_hit(pointer_ray: &Ray, hit: &RaycastHit, id: u64) -> Self {
+        Self {
+            contact_point: local_hit_point(pointer_ray, hit.distance, hit.voxel),
+            distance: hit.distance,
+            id
+        }
+    }
+
+    /// Gets the position toward which the entity is pulled, along the pointer.
+    pub fn target_position(&self, pointer_ray: &Ray) -> WorldVec {
+        pointer_ray.position + (self.distance * pointer_ray.direction).into()
+    }
+}
+
+/// An entity that was thrown or deleted, and is pulled along its path for a short time.
+#[derive(Copy, Clone, Debug)]
+struct ThrownObject {
+    /// The body-local point by which the entity is pulled.
+    contact_point: Vec3A,
+    /// How long the entity is pulled along its path, in seconds.
+    duration: f32,
+    /// The time since the entity was thrown, in seconds.
+    elapsed: f32,
+    /// The ID of the entity.
+    id: u64,
+    /// The voxel-space position at which the entity was released.
+    origin: Vec3A,
+    /// The velocity with which the entity was thrown, in voxels per second.
+    velocity: Vec3A
+}
+
+/// Gets the body-local point at which a ray struck the given body-local voxel, after travelling the given distance.
+fn local_hit_point(ray: &Ray, distance: f32, voxel: IVec3) -> Vec3A {
+    /// How far past the surface the hit point is sampled, so that it falls within the struck voxel rather than on its boundary.
+    const SURFACE_BIAS: f32 = 1e-3;
+
+    let inside = to_voxel_space(ray.position) + (distance + SURFACE_BIAS) * ray.direction;
+    voxel.as_vec3a() + (inside - inside.floor()).clamp(Vec3A::ZERO, Vec3A::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a ray from the given origin toward the given point.
+    fn ray_toward(origin: Vec3A, target: Vec3A) -> Ray {
+        Ray {
+            position: origin.into(),
+            direction: (target - origin).normalize(),
+            max_distance: 256.0
+        }
+    }
+
+    #[test]
+    fn grid_aligned_hits_map_to_the_struck_point() {
+        let voxel = ivec3(3, -2, 5);
+        let hit_points = [
+            vec3a(3.0, -1.5, 5.5), vec3a(4.0, -1.2, 5.9), vec3a(3.1, -2.0, 5.1),
+            vec3a(3.9, -1.0, 5.95), vec3a(3.02, -1.98, 5.0), vec3a(3.5, -1.5, 6.0)
+        ];
+
+        for hit_point in hit_points {
+            let outward = hit_point - (voxel.as_vec3a() + Vec3A::splat(0.5));
+            let origin = hit_point + 10.0 * outward.normalize();
+            let ray = ray_toward(origin, hit_point);
+            let contact_point = local_hit_point(&ray, origin.distance(hit_point), voxel);
+            assert!(contact_point.distance(hit_point) < 0.01, "{hit_point} mapped to {contact_point}");
+        }
+    }
+
+    #[test]
+    fn offset_bodies_keep_the_point_on_the_struck_voxel() {
+        let ray = ray_toward(vec3a(20.3, 7.7, -4.2), vec3a(0.6, 0.4, 0.1));
+        let contact_point = local_hit_point(&ray, 12.5, ivec3(-1, 0, 2));
+        assert!(contact_point.cmpge(vec3a(-1.0, 0.0, 2.0)).all() && contact_point.cmple(vec3a(0.0, 1.0, 3.0)).all());
+    }
+
+    #[test]
+    fn edge_hits_are_not_moved_to_the_voxel_center() {
+        let hit_point = vec3a(1.0, 0.98, 0.02);
+        let ray = ray_toward(vec3a(8.0, 0.98, 0.02), hit_point);
+        let contact_point = local_hit_point(&ray, 7.0, IVec3::ZERO);
+        assert!(contact_point.distance(hit_point) < 0.01);
+        assert!(0.4 < contact_point.distance(Vec3A::splat(0.5)));
+    }
+
+    #[test]
+    fn rotating_turns_the_contact_point_around_the_origin() {
+        let contact_point = vec3a(2.0, 1.0, -0.5);
+        let mut gun = PhysicsGun::default();
+        gun.rotate(vec2(100.0, 0.0));
+        assert!(gun.held.is_none());
+
+        gun.grab(HeldObject { contact_point, distance: 5.0, id: 7 });
+        gun.rotate(vec2(100.0, -40.0));
+        let rotated = gun.held.expect("Held entity was released").contact_point;
+        assert!(0.1 < rotated.distance(contact_point));
+        assert!((rotated.length() - contact_point.length()).abs() < 1e-4);
+
+        gun.rotate(vec2(0.0, 0.0));
+        assert_eq!(gun.held.map(|x| x.contact_point), Some(rotated));
+    }
+
+    #[test]
+    fn discarded_entities_are_released_and_dragged_downward() {
+        let ray = ray_toward(Vec3A::ZERO, Vec3A::X);
+        let mut gun = PhysicsGun::default();
+        gun.grab(HeldObject { contact_point: Vec3A::ZERO, distance: 5.0, id: 3 });
+        gun.discard(3, Vec3A::ONE, vec3a(5.0, 0.0, 0.0));
+        assert!(gun.held.is_none());
+
+        let Some(Some(drag)) = gun.update_drag(&ray, 1.0) else { panic!("Discarded entity was not dragged") };
+        assert_eq!((drag.id, drag.contact_point), (3, Vec3A::ONE));
+        assert!(to_voxel_space(drag.target_position).y < -1000.0);
+
+        assert!(matches!(gun.update_drag(&ray, 1.0), Some(None)));
+        assert!(gun.update_drag(&ray, 1.0).is_none());
+    }
+}
@@ -0,0 +1,163 @@
+use voxel_engine::input::*;
+use voxel_std_types::bindings::*;
+
+/// Lists the actions of every mod, and allows the player to bind them to different inputs.
+/// The engine fixes bindings when actions are defined, so changes are saved to the configuration
+/// file and take effect the next time that the game starts. They are not applied while playing.
+#[derive(Clone, Debug)]
+pub struct RebindingMenu {
+    /// The index of the action and the binding slot awaiting a new input, if any.
+    capturing: Option<(usize, usize)>,
+    /// Detects the gamepad input that the player chose.
+    gamepad: GamepadCapture,
+    /// Whether the menu window is shown.
+    pub open: bool,
+    /// The overrides chosen by the player, including those made since the game started.
+    overrides: BindingOverrides,
+    /// A message describing the result of the last change.
+    status: Option<String>
+}
+
+impl RebindingMenu {
+    /// Creates a menu that begins with the overrides in the configuration file, and captures gamepad input with the given actions.
+    pub fn load(gamepad: GamepadCapture) -> Self {
+        Self {
+            capturing: None,
+            gamepad,
+            open: false,
+            overrides: BindingOverrides::load().0,
+            status: None
+        }
+    }
+
+    /// Infers the player's keyboard layout from key presses if it has not yet been chosen, and saves it
+    /// so that positional bindings follow it at the next start. Returns a notice if a layout was detected.
+    pub fn detect_layout(&mut self, egui_ctx: &egui::Context) -> Option<String> {
+        if self.overrides.layout.is_some() {
+            return None;
+        }
+
+        let detected = egui_ctx.input(|input| input.events.iter().find_map(|event| match *event {
+            egui::Event::Key { key, physical_key: Some(physical_key), pressed: true, .. } => KeyboardLayout::detect(key, physical_key),
+            _ => None
+        }))?;
+
+        self.overrides.layout = Some(detected);
+        self.save();
+        Some(format!("Detected the {} keyboard layout; default keys will follow it after restarting", detected.name()))
+    }
+
+    /// Draws the menu window, if it is open, and records any input that the player chose.
+    pub fn draw(&mut self, egui_ctx: &egui::Context, input: &dyn Input, actions: &[BoundAction]) {
+        if !self.open {
+            self.capturing = None;
+            return;
+        }
+
+        if let Some((action, slot)) = self.capturing {
+            match egui_ctx.input(capture_input).or_else(|| self.gamepad.capture(input)) {
+                Some(RawInput::Key(Key::Escape)) => self.capturing = None,
+                Some(input) => {
+                    self.overrides.set(&actions[action].group, &actions[action].name, slot, input);
+                    self.capturing = None;
+                    self.save();
+                },
+                None => {}
+            }
+        }
+
+        let effective = actions.iter()
+            .map(|x| (0..x.defaults.len()).map(|slot| self.input_for(x, slot)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut open = self.open;
+        egui::Window::new("Key bindings").open(&mut open).show(egui_ctx, |ui| {
+            let layout = self.overrides.layout;
+            let layout_name = layout.map(KeyboardLayout::name).unwrap_or("Not detected");
+            egui::ComboBox::from_label("Keyboard layout").selected_text(layout_name).show_ui(ui, |ui| {
+                for option in KeyboardLayout::ALL {
+                    ui.selectable_value(&mut self.overrides.layout, Some(option), option.name());
+                }
+            });
+
+            if self.overrides.layout != layout {
+                self.save();
+            }
+
+            match self.capturing {
+                Some(_) => ui.label("Press a key, mouse button, or gamepad input, or Escape to cancel"),
+                None => ui.label("Click a binding to change it. Changes take effect after restarting the game.")
+            };
+
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("key_bindings").num_columns(3).striped(true).show(ui, |ui| {
+                    for (index, action) in actions.iter().enumerate() {
+                        if index == 0 || actions[index - 1].group != action.group {
+                            ui.strong(&action.group);
+                            ui.end_row();
+                        }
+
+                        ui.label(&action.name);
+                        ui.horizontal(|ui| {
+                            for (slot, &input) in effective[index].iter().enumerate() {
+                                let capturing = self.capturing == Some((index, slot));
+                                let others = Self::others_bound_to(actions, &effective, index, input);
+                                let text = if capturing { "...".to_string() } else { describe_input(input) };
+                                let color = [ui.visuals().text_color(), egui::Color32::LIGHT_RED][!others.is_empty() as usize];
+
+                                let mut response = ui.selectable_label(capturing, egui::RichText::new(text).color(color));
+                                if !others.is_empty() {
+                                    response = response.on_hover_text(format!("Also bound to {}", others.join(", ")));
+                                }
+
+                                if response.clicked() {
+                                    self.capturing = Some((index, slot));
+                                }
+                            }
+                        });
+
+                        if ui.add_enabled(self.overrides.is_overridden(&action.group, &action.name), egui::Button::new("Reset")).clicked() {
+                            self.overrides.reset(&action.group, &action.name);
+                            self.save();
+                        }
+
+                        ui.end_row();
+                    }
+                });
+            });
+
+            if let Some(status) = &self.status {
+                ui.separator();
+                ui.label(status);
+            }
+        });
+
+        self.open = open;
+    }
+
+    /// Gets the input bound to a slot of an action, with the player's overrides applied.
+    fn input_for(&self, action: &BoundAction, slot: usize) -> RawInput {
+        self.overrides.get(&action.group, &action.name, slot).unwrap_or(action.defaults[slot])
+    }
+
+    /// Gets the names of the other actions that are bound to the given input,
+    /// excluding those that are allowed to share inputs with the action.
+    fn others_bound_to(actions: &[BoundAction], effective: &[Vec<RawInput>], index: usize, input: RawInput) -> Vec<String> {
+        let action = &actions[index];
+        actions.iter()
+            .zip(effective)
+            .enumerate()
+            .filter(|&(other, (_, inputs))| other != index && inputs.contains(&input))
+            .filter(|&(_, (other, _))| !(other.group == action.group && action.shares_inputs_with.contains(&other.name)))
+            .map(|(_, (action, _))| format!("{} ({})", action.name, action.group))
+            .collect()
+    }
+
+    /// Writes the overrides to disk, and describes the result.
+    fn save(&mut self) {
+        self.status = Some(match self.overrides.save() {
+            Ok(()) => format!("Saved to {}. Changes take effect after restarting.", BindingOverrides::CONFIG_PATH),
+            Err(error) => format!("Failed to save {}: {error}", BindingOverrides::CONFIG_PATH)
+        });
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::*;
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+
+/// Reshapes terrain by changing the heights of the voxel columns around a target.
+#[derive(Copy, Clone, Debug)]
+pub struct Sculptor {
+    /// Whether placing voxels sculpts terrain instead.
+    pub enabled: bool,
+    /// The height to which terrain is flattened, chosen by the first sculpt after flattening is selected.
+    pub flatten_height: Option<i32>,
+    /// The change made to the terrain.
+    pub operation: SculptOperation,
+    /// The largest number of voxels by which a column may change in a single step.
+    pub strength: u32
+}
+
+impl Sculptor {
+    /// The largest strength that the sculptor may have.
+    pub const MAX_STRENGTH: u32 = 8;
+
+    /// The vertical distance below the target voxel within which column surfaces are found.
+    const PROBE_DEPTH: f32 = 32.0;
+
+    /// Prepares to sculpt around the given target voxel. When flattening, the first stroke
+    /// chooses the height of the target as the one to which all later strokes flatten.
+    pub fn begin_stroke(&mut self, target: IVec3) {
+        if self.operation == SculptOperation::Flatten {
+            self.flatten_height = self.flatten_height.or(Some(target.y));
+        }
+    }
+
+    /// Switches sculpting mode on or off, forgetting the flatten height.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.flatten_height = None;
+    }
+
+    /// Moves to the next operation, forgetting the flatten height.
+    pub fn cycle_operation(&mut self) {
+        self.operation = self.operation.next();
+        self.flatten_height = None;
+    }
+
+    /// Moves to the next strength, wrapping back to one after the largest.
+    pub fn cycle_strength(&mut self) {
+        self.strength = self.strength % Self::MAX_STRENGTH + 1;
+    }
+
+    /// Gets a short description of the sculptor for display.
+    pub fn describe(&self, radius: u32) -> String {
+        format!("Sculpt: {}, strength {}, radius {radius}", self.operation.name(), self.strength)
+    }
+
+    /// Gets the horizontal columns affected by sculpting around the given voxel,
+    /// alongside the columns around them that are needed for smoothing.
+    pub fn probed_columns(center: IVec3, radius: u32) -> impl Iterator<Item = IVec2> {
+        let reach = radius as i32 + 1;
+        (-reach..=reach).flat_map(move |x| (-reach..=reach).map(move |z| center.xz() + ivec2(x, z)))
+    }
+
+    /// Gets the ray that finds the surface of a column. It is cast downward from the layer
+    /// just above the target voxel, so that overhangs are not mistaken for the surface.
+    pub fn probe_ray(target: IVec3, column: IVec2) -> Ray {
+        Ray {
+            position: vec3a(column.x as f32 + 0.5, target.y as f32 + 1.5, column.y as f32 + 0.5).into(),
+            direction: Vec3A::NEG_Y,
+            max_distance: Self::PROBE_DEPTH + 1.5
+        }
+    }
+
+    /// Gets the height of a column from the voxel struck by its probe ray,
+    /// or nothing if the column already rises above the target voxel.
+    pub fn surface_height(target: IVec3, surface: IVec3) -> Option<i32> {
+        (surface.y <= target.y).then_some(surface.y)
+    }
+
+    /// Gets the voxels of a column that must be deleted to lower it from the given height to the goal.
+    pub fn removed_voxels(column: IVec2, height: i32, goal: i32) -> impl Iterator<Item = IVec3> {
+        (goal + 1..=height).map(move |y| ivec3(column.x, y, column.y))
+    }
+
+    /// Gets the voxels of a column that must be placed to raise it from the given height to the goal.
+    pub fn added_voxels(column: IVec2, height: i32, goal: i32) -> impl Iterator<Item = IVec3> {
+        (height + 1..=goal).map(move |y| ivec3(column.x, y, column.y))
+    }
+
+    /// Gets the current and new surface height of each column within the given radius of the target voxel.
+    /// The heights of the surrounding columns are provided, and columns without a known height are left unchanged.
+    /// Columns change more slowly toward the edge of the radius.
+    pub fn sculpt(&self, center: IVec3, radius: u32, heights: &HashMap<IVec2, i32>) -> Vec<(IVec2, i32, i32)> {
+        let reach = radius as f32 + 1.0;
+        let radius = radius as i32;
+        let mut result = Vec::new();
+
+        for x in -radius..=radius {
+            for z in -radius..=radius {
+                let offset = ivec2(x, z);
+                let distance = offset.as_vec2().length();
+                let column = center.xz() + offset;
+
+                if let Some(&height) = heights.get(&column).filter(|_| distance <= radius as f32 + 0.5) {
+                    let step = (self.strength as f32 * (1.0 - distance / reach)).ceil() as i32;
+                    let goal = match self.operation {
+                        SculptOperation::Raise => height + step,
+                        SculptOperation::Lower => height - step,
+                        SculptOperation::Smooth => Self::neighborhood_average(column, heights).unwrap_or(height),
+                        SculptOperation::Flatten => self.flatten_height.unwrap_or(height)
+                    };
+
+                    result.push((column, height, goal.clamp(height - step, height + step)));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Gets the rounded average height of a column and its eight neighbors.
+    fn neighborhood_average(column: IVec2, heights: &HashMap<IVec2, i32>) -> Option<i32> {
+        let neighbors = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |z| column + ivec2(x, z)))
+            .filter_map(|x| heights.get(&x))
+            .collect::<Vec<_>>();
+
+        (!neighbors.is_empty()).then(|| (neighbors.iter().map(|&&x| x as f32).sum::<f32>() / neighbors.len() as f32).round() as i32)
+    }
+}
+
+impl Default for Sculptor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            flatten_height: None,
+            operation: SculptOperation::default(),
+            strength: 1
+        }
+    }
+}
+
+/// The changes that the sculptor may make to terrain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SculptOperation {
+    /// Adds voxels to the top of each column.
+    #[default]
+    Raise,
+    /// Removes voxels from the top of each column.
+    Lower,
+    /// Moves each column toward the average height of its neighbors.
+    Smooth,
+    /// Moves each column toward the height at which flattening began.
+    Flatten
+}
+
+impl SculptOperation {
+    /// Gets the human-readable name of the operation.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Raise => "Raise",
+            Self::Lower => "Lower",
+            Self::Smooth => "Smooth",
+            Self::Flatten => "Flatten"
+        }
+    }
+
+    /// Gets the operation that follows this one when cycling through operations.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Raise => Self::Lower,
+            Self::Lower => Self::Smooth,
+            Self::Smooth => Self::Flatten,
+            Self::Flatten => Self::Raise
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voxel_std_types::targeting::*;
+
+    /// Creates a sculptor with the given operation and strength.
+    fn sculptor(operation: SculptOperation, strength: u32) -> Sculptor {
+        Sculptor {
+            enabled: true,
+            flatten_height: None,
+            operation,
+            strength
+        }
+    }
+
+    /// Creates heights for every probed column around the origin, using the given function of each column.
+    fn heights(radius: u32, height: impl Fn(IVec2) -> i32) -> HashMap<IVec2, i32> {
+        Sculptor::probed_columns(IVec3::ZERO, radius).map(|x| (x, height(x))).collect()
+    }
+
+    /// Gets the new height of the given column from the result of a sculpt.
+    fn goal(result: &[(IVec2, i32, i32)], column: IVec2) -> Option<i32> {
+        result.iter().find(|x| x.0 == column).map(|x| x.2)
+    }
+
+    #[test]
+    fn raising_falls_off_toward_the_edge() {
+        let result = sculptor(SculptOperation::Raise, 4).sculpt(IVec3::ZERO, 2, &heights(2, |_| 0));
+
+        assert_eq!(result.len(), 21);
+        assert_eq!(goal(&result, IVec2::ZERO), Some(4));
+        assert_eq!(goal(&result, ivec2(1, 0)), Some(3));
+        assert_eq!(goal(&result, ivec2(2, 0)), Some(2));
+        assert_eq!(goal(&result, ivec2(2, 1)), Some(2));
+        assert_eq!(goal(&result, ivec2(2, 2)), None);
+    }
+
+    #[test]
+    fn lowering_mirrors_raising() {
+        let result = sculptor(SculptOperation::Lower, 4).sculpt(IVec3::ZERO, 1, &heights(1, |_| 0));
+
+        assert!(result.iter().all(|x| x.1 == 0));
+        assert_eq!(goal(&result, IVec2::ZERO), Some(-4));
+        assert_eq!(goal(&result, ivec2(0, -1)), Some(-2));
+        assert_eq!(goal(&result, ivec2(1, 1)), Some(-2));
+    }
+
+    #[test]
+    fn columns_without_heights_are_unchanged() {
+        let mut heights = heights(1, |_| 0);
+        heights.remove(&ivec2(1, 0));
+        let result = sculptor(SculptOperation::Raise, 1).sculpt(IVec3::ZERO, 1, &heights);
+
+        assert_eq!(result.len(), 8);
+        assert_eq!(goal(&result, ivec2(1, 0)), None);
+    }
+
+    #[test]
+    fn smoothing_moves_columns_toward_their_neighbors() {
+        let peak = heights(0, |x| [0, 9][(x == IVec2::ZERO) as usize]);
+        assert_eq!(sculptor(SculptOperation::Smooth, 8).sculpt(IVec3::ZERO, 0, &peak), vec![(IVec2::ZERO, 9, 1)]);
+        assert_eq!(sculptor(SculptOperation::Smooth, 1).sculpt(IVec3::ZERO, 0, &peak), vec![(IVec2::ZERO, 9, 8)]);
+
+        let result = sculptor(SculptOperation::Smooth, 8).sculpt(IVec3::ZERO, 0, &heights(0, |x| x.x));
+        assert_eq!(result, vec![(IVec2::ZERO, 0, 0)]);
+    }
+
+    #[test]
+    fn flattening_uses_the_height_of_the_first_stroke() {
+        let mut sculptor = sculptor(SculptOperation::Flatten, 8);
+        sculptor.begin_stroke(ivec3(0, 3, 0));
+        sculptor.begin_stroke(ivec3(5, 7, 5));
+        assert_eq!(sculptor.flatten_height, Some(3));
+
+        let result = sculptor.sculpt(IVec3::ZERO, 1, &heights(1, |x| x.x * 4));
+        assert_eq!(goal(&result, ivec2(-1, 0)), Some(0));
+        assert_eq!(goal(&result, IVec2::ZERO), Some(3));
+        assert_eq!(goal(&result, ivec2(1, 0)), Some(3));
+        assert_eq!(goal(&result, ivec2(1, 1)), Some(3));
+    }
+
+    #[test]
+    fn switching_modes_forgets_the_flatten_height() {
+        let mut sculptor = sculptor(SculptOperation::Flatten, 1);
+        sculptor.begin_stroke(ivec3(0, 3, 0));
+        sculptor.cycle_strength();
+        assert_eq!(sculptor.flatten_height, Some(3));
+
+        sculptor.toggle();
+        assert_eq!(sculptor.flatten_height, None);
+
+        sculptor.toggle();
+        sculptor.begin_stroke(ivec3(0, 5, 0));
+        sculptor.cycle_operation();
+        assert_eq!(sculptor.flatten_height, None);
+        assert_eq!(sculptor.operation, SculptOperation::Raise);
+    }
+
+    #[test]
+    fn only_flattening_chooses_a_height() {
+        let mut sculptor = sculptor(SculptOperation::Raise, 1);
+        sculptor.begin_stroke(ivec3(0, 3, 0));
+        assert_eq!(sculptor.flatten_height, None);
+
+        let result = Sculptor { operation: SculptOperation::Flatten, ..sculptor }.sculpt(IVec3::ZERO, 0, &heights(0, |_| 2));
+        assert_eq!(result, vec![(IVec2::ZERO, 2, 2)]);
+    }
+
+    #[test]
+    fn probes_start_above_the_target_and_ignore_higher_surfaces() {
+        let probe = Sculptor::probe_ray(ivec3(4, 10, -2), ivec2(5, -3));
+        assert_eq!(to_voxel_space(probe.position), vec3a(5.5, 11.5, -2.5));
+        assert_eq!(probe.direction, Vec3A::NEG_Y);
+
+        assert_eq!(Sculptor::surface_height(ivec3(4, 10, -2), ivec3(5, 10, -3)), Some(10));
+        assert_eq!(Sculptor::surface_height(ivec3(4, 10, -2), ivec3(5, -6, -3)), Some(-6));
+        assert_eq!(Sculptor::surface_height(ivec3(4, 10, -2), ivec3(5, 11, -3)), None);
+        assert_eq!(Sculptor::probed_columns(IVec3::ZERO, 1).count(), 25);
+    }
+
+    #[test]
+    fn columns_change_one_voxel_per_unit_of_height() {
+        let column = ivec2(2, -1);
+        assert_eq!(Sculptor::added_voxels(column, 1, 3).collect::<Vec<_>>(), vec![ivec3(2, 2, -1), ivec3(2, 3, -1)]);
+        assert_eq!(Sculptor::removed_voxels(column, 3, 1).collect::<Vec<_>>(), vec![ivec3(2, 2, -1), ivec3(2, 3, -1)]);
+        assert_eq!(Sculptor::added_voxels(column, 3, 1).count(), 0);
+        assert_eq!(Sculptor::removed_voxels(column, 1, 3).count(), 0);
+    }
+}
@@ -0,0 +1,115 @@
+use serde::*;
+use voxel_std_types::spawning::*;
+
+/// Lists the physics objects that the player may spawn, and tracks which one is chosen.
+#[derive(Clone, Debug)]
+pub struct SpawnMenu {
+    /// The objects in the menu, in display order.
+    objects: Vec<SpawnableObject>,
+    /// Whether the menu window is shown.
+    pub open: bool,
+    /// The index of the chosen object.
+    selected: usize
+}
+
+impl SpawnMenu {
+    /// The path of the file from which spawnable objects are loaded.
+    const CONFIG_PATH: &'static str = "config/physics_objects.toml";
+
+    /// Loads the spawnable objects from the configuration file. If no file exists, the object kinds that the
+    /// controller cycled through before the menu existed are listed under generic names, since the engine does
+    /// not describe its object kinds. If the file is malformed, those objects are returned alongside a description of the problem.
+    pub fn load() -> (Self, Option<String>) {
+        match std::fs::read_to_string(Self::CONFIG_PATH) {
+            Ok(text) => match toml::from_str::<SpawnMenuConfig>(&text) {
+                Ok(config) if !config.object.is_empty() => (Self { objects: config.object, ..Self::default() }, None),
+                Ok(_) => (Self::default(), Some(format!("{} contains no objects", Self::CONFIG_PATH))),
+                Err(error) => (Self::default(), Some(format!("Failed to parse {}: {error}", Self::CONFIG_PATH)))
+            },
+            Err(_) => (Self::default(), None)
+        }
+    }
+
+    /// Creates a menu entry for one of the builtin object kinds.
+    fn builtin(name: &str, color: [u8; 4], kind: u32) -> SpawnableObject {
+        SpawnableObject {
+            color,
+            icon: None,
+            kind,
+            name: name.to_string()
+        }
+    }
+
+    /// Adds an object to the menu, replacing any object of the same kind.
+    pub fn add(&mut self, object: SpawnableObject) {
+        if let Some(existing) = self.objects.iter_mut().find(|x| x.kind == object.kind) {
+            *existing = object;
+        }
+        else {
+            self.objects.push(object);
+        }
+    }
+
+    /// Gets all objects in the menu.
+    pub fn objects(&self) -> &[SpawnableObject] {
+        &self.objects
+    }
+
+    /// Gets the object that the player has chosen to spawn.
+    pub fn selected(&self) -> Option<&SpawnableObject> {
+        self.objects.get(self.selected.min(self.objects.len().saturating_sub(1)))
+    }
+
+    /// Chooses the first object in the menu again.
+    pub fn reset_selection(&mut self) {
+        self.selected = 0;
+    }
+
+    /// Draws the menu window, if it is open, allowing the player to choose an object.
+    pub fn draw(&mut self, egui_ctx: &egui::Context) {
+        /// The width and height of the swatch that previews each object.
+        const SWATCH_SIZE: f32 = 20.0;
+
+        let objects = &self.objects;
+        let selected = &mut self.selected;
+
+        egui::Window::new("Spawn").open(&mut self.open).resizable(false).show(egui_ctx, |ui| {
+            for (index, object) in objects.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let (swatch, _) = ui.allocate_exact_size(egui::Vec2::splat(SWATCH_SIZE), egui::Sense::hover());
+                    let [r, g, b, a] = object.color;
+                    ui.painter().rect_filled(swatch, 2.0, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+
+                    if let Some(icon) = &object.icon {
+                        ui.painter().text(swatch.center(), egui::Align2::CENTER_CENTER, icon, egui::FontId::proportional(14.0), egui::Color32::WHITE);
+                    }
+
+                    ui.selectable_value(selected, index, &object.name);
+                });
+            }
+        });
+    }
+}
+
+impl Default for SpawnMenu {
+    fn default() -> Self {
+        Self {
+            objects: vec![
+                Self::builtin("Object 1", [200, 120, 60, 255], 1),
+                Self::builtin("Object 2", [60, 160, 200, 255], 2),
+                Self::builtin("Object 3", [160, 200, 60, 255], 3),
+                Self::builtin("Object 4", [200, 60, 160, 255], 4)
+            ],
+            open: false,
+            selected: 0
+        }
+    }
+}
+
+/// The on-disk representation of the spawn menu.
+#[derive(Clone, Debug, Deserialize)]
+struct SpawnMenuConfig {
+    /// The objects of the menu, in display order.
+    #[serde(default)]
+    object: Vec<SpawnableObject>
+}
@@ -0,0 +1,215 @@
+use voxel_engine::math::*;
+
+/// On-screen controls for touch and pen input. A virtual joystick on the left half of the screen
+/// moves the player, drags on the right half turn the camera, pinches push or pull a held entity,
+/// and buttons in the bottom-right corner stand in for the most common actions. The controls
+/// appear once a touch is detected, and hide again when a key is pressed.
+#[derive(Clone, Debug, Default)]
+pub struct TouchControls {
+    /// Whether touch input has been detected since a key was last pressed.
+    active: bool,
+    /// The buttons being held, alongside the touches holding them.
+    buttons: Vec<(egui::TouchId, TouchButton)>,
+    /// The touches used for turning the camera and pinching, alongside their latest positions.
+    look: Vec<(egui::TouchId, egui::Pos2)>,
+    /// How far the camera should turn this frame, in points.
+    look_delta: Vec2,
+    /// How much the distance between two look touches changed this frame, in points.
+    pinch_delta: f32,
+    /// The buttons that were pressed this frame.
+    pressed: Vec<TouchButton>,
+    /// The touch controlling the joystick, alongside the position where it began and its latest position.
+    stick: Option<(egui::TouchId, egui::Pos2, egui::Pos2)>
+}
+
+impl TouchControls {
+    /// The distance that a touch must move from where it began to tilt the joystick fully.
+    const STICK_RADIUS: f32 = 70.0;
+
+    /// Whether any touch is currently held on the joystick, a button, or the look area.
+    pub fn in_use(&self) -> bool {
+        self.stick.is_some() || !self.buttons.is_empty() || !self.look.is_empty()
+    }
+
+    /// Reads the touch events for this frame. Touches that begin over an egui window are left to that window.
+    pub fn update(&mut self, egui_ctx: &egui::Context) {
+        self.look_delta = Vec2::ZERO;
+        self.pinch_delta = 0.0;
+        self.pressed.clear();
+
+        let screen = egui_ctx.screen_rect();
+        let events = egui_ctx.input(|x| x.events.clone());
+        for event in events {
+            match event {
+                egui::Event::Touch { id, phase, pos, .. } => {
+                    self.active = true;
+                    match phase {
+                        egui::TouchPhase::Start => self.begin_touch(egui_ctx, screen, id, pos),
+                        egui::TouchPhase::Move => self.move_touch(id, pos),
+                        egui::TouchPhase::End | egui::TouchPhase::Cancel => self.end_touch(id)
+                    }
+                },
+                egui::Event::Key { pressed: true, .. } => self.active = false,
+                _ => {}
+            }
+        }
+    }
+
+    /// Gets the tilt of the joystick, with the x-axis pointing right and the y-axis pointing forward.
+    pub fn movement(&self) -> Vec2 {
+        self.stick
+            .map(|(_, origin, current)| (vec2(current.x - origin.x, origin.y - current.y) / Self::STICK_RADIUS).clamp_length_max(1.0))
+            .unwrap_or_default()
+    }
+
+    /// Gets how far the camera should turn this frame, in the same units as pointer movement.
+    pub fn look_delta(&self) -> Vec2 {
+        self.look_delta
+    }
+
+    /// Gets how far a held entity should be pushed away this frame, in steps of the scroll wheel.
+    pub fn pinch_steps(&self) -> f32 {
+        /// The change in distance between two touches that corresponds to one step of the scroll wheel.
+        const PINCH_STEP: f32 = 40.0;
+
+        self.pinch_delta / PINCH_STEP
+    }
+
+    /// Gets the state of an on-screen button.
+    pub fn button(&self, button: TouchButton) -> TouchButtonState {
+        TouchButtonState {
+            held: self.buttons.iter().any(|x| x.1 == button),
+            pressed: self.pressed.contains(&button)
+        }
+    }
+
+    /// Draws the joystick and buttons, if the controls are active.
+    pub fn draw(&self, painter: &egui::Painter) {
+        /// The radius of the joystick knob.
+        const KNOB_RADIUS: f32 = 28.0;
+
+        if !self.active {
+            return;
+        }
+
+        let screen = painter.clip_rect();
+        let fill = egui::Color32::from_white_alpha(40);
+        let stroke = egui::Stroke::new(2.0, egui::Color32::from_white_alpha(120));
+
+        let origin = self.stick.map(|x| x.1).unwrap_or(screen.left_bottom() + egui::vec2(1.5, -1.5) * Self::STICK_RADIUS);
+        let tilt = self.movement();
+        painter.circle(origin, Self::STICK_RADIUS, fill, stroke);
+        painter.circle_filled(origin + Self::STICK_RADIUS * egui::vec2(tilt.x, -tilt.y), KNOB_RADIUS, egui::Color32::from_white_alpha(120));
+
+        for button in TouchButton::ALL {
+            let held = self.button(button).held;
+            painter.circle(button.center(screen), TouchButton::RADIUS, [fill, egui::Color32::from_white_alpha(120)][held as usize], stroke);
+            painter.text(button.center(screen), egui::Align2::CENTER_CENTER, button.label(), egui::FontId::proportional(14.0), egui::Color32::WHITE);
+        }
+    }
+
+    /// Assigns a new touch to the button beneath it, the joystick, or the look area.
+    fn begin_touch(&mut self, egui_ctx: &egui::Context, screen: egui::Rect, id: egui::TouchId, pos: egui::Pos2) {
+        if egui_ctx.layer_id_at(pos).is_some_and(|x| x.order != egui::Order::Background) {
+            return;
+        }
+
+        if let Some(button) = TouchButton::ALL.into_iter().find(|x| x.center(screen).distance(pos) <= TouchButton::RADIUS) {
+            self.buttons.push((id, button));
+            self.pressed.push(button);
+        }
+        else if pos.x < screen.center().x {
+            if self.stick.is_none() {
+                self.stick = Some((id, pos, pos));
+            }
+        }
+        else {
+            self.look.push((id, pos));
+        }
+    }
+
+    /// Moves the joystick, or turns the camera with a single look touch and pinches with two.
+    fn move_touch(&mut self, id: egui::TouchId, pos: egui::Pos2) {
+        if let Some(stick) = self.stick.as_mut().filter(|x| x.0 == id) {
+            stick.2 = pos;
+        }
+
+        let Some(index) = self.look.iter().position(|x| x.0 == id) else {
+            return;
+        };
+
+        let previous = std::mem::replace(&mut self.look[index].1, pos);
+        if self.look.len() == 1 {
+            self.look_delta += vec2(pos.x - previous.x, pos.y - previous.y);
+        }
+        else if index < 2 {
+            let other = self.look[1 - index].1;
+            self.pinch_delta += other.distance(pos) - other.distance(previous);
+        }
+    }
+
+    /// Releases whatever the touch was controlling.
+    fn end_touch(&mut self, id: egui::TouchId) {
+        self.buttons.retain(|x| x.0 != id);
+        self.look.retain(|x| x.0 != id);
+
+        if self.stick.is_some_and(|x| x.0 == id) {
+            self.stick = None;
+        }
+    }
+}
+
+/// An on-screen button that stands in for one of the player's actions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TouchButton {
+    /// Places voxels, like the place action.
+    Place,
+    /// Deletes voxels, like the delete action.
+    Delete,
+    /// Grabs the entity under the crosshair, like the drag action.
+    Grab,
+    /// Throws the held entity.
+    Throw,
+    /// Moves the player upward, like the jump action.
+    Up,
+    /// Moves the player downward, like the sneak action.
+    Down
+}
+
+impl TouchButton {
+    /// Every button, in the order in which they are drawn.
+    pub const ALL: [Self; 6] = [Self::Place, Self::Delete, Self::Grab, Self::Throw, Self::Up, Self::Down];
+    /// The radius of each button.
+    const RADIUS: f32 = 32.0;
+
+    /// Gets the text drawn on the button.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Place => "Place",
+            Self::Delete => "Delete",
+            Self::Grab => "Grab",
+            Self::Throw => "Throw",
+            Self::Up => "Up",
+            Self::Down => "Down"
+        }
+    }
+
+    /// Gets the center of the button, which is arranged in a grid in the bottom-right corner of the screen.
+    fn center(self, screen: egui::Rect) -> egui::Pos2 {
+        /// The distance between the centers of adjacent buttons.
+        const SPACING: f32 = 80.0;
+
+        let index = Self::ALL.iter().position(|&x| x == self).unwrap_or_default();
+        let (column, row) = (index % 2, index / 2);
+        screen.right_bottom() - SPACING * egui::vec2(1.0 + column as f32, 1.0 + row as f32)
+    }
+}
+
+/// The state of an on-screen button during a frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TouchButtonState {
+    /// Whether the button is being held.
+    pub held: bool,
+    /// Whether the button was pressed during this frame.
+    pub pressed: bool
+}
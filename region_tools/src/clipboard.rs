@@ -0,0 +1,164 @@
+use schematic::*;
+use voxel_engine::math::*;
+
+/// A copied or imported box of voxels that may be rotated, mirrored, and pasted elsewhere.
+#[derive(Clone, Debug, Default)]
+pub struct Clipboard {
+    /// The number of voxels along each axis of the copied box.
+    size: IVec3,
+    /// The filled voxels of the box, as offsets from its minimum corner alongside their materials.
+    voxels: Vec<(IVec3, u32)>
+}
+
+impl Clipboard {
+    /// Creates a clipboard from a box of the given size and its filled voxels.
+    pub fn new(size: IVec3, voxels: Vec<(IVec3, u32)>) -> Self {
+        Self { size, voxels }
+    }
+
+    /// Creates a clipboard that tightly bounds the given voxels, which may have any position.
+    pub fn from_voxels(mut voxels: Vec<(IVec3, u32)>) -> Self {
+        let min = voxels.iter().fold(IVec3::MAX, |acc, &(x, _)| acc.min(x));
+        let max = voxels.iter().fold(IVec3::MIN, |acc, &(x, _)| acc.max(x));
+
+        for (offset, _) in &mut voxels {
+            *offset -= min;
+        }
+
+        Self {
+            size: [IVec3::ZERO, max - min + IVec3::ONE][!voxels.is_empty() as usize],
+            voxels
+        }
+    }
+
+    /// Creates a clipboard holding the filled voxels of a schematic.
+    pub fn from_schematic(schematic: &Schematic) -> Self {
+        Self {
+            size: UVec3::from_array(schematic.size()).as_ivec3(),
+            voxels: schematic.filled_voxels().map(|(position, material)| (UVec3::from_array(position).as_ivec3(), material)).collect()
+        }
+    }
+
+    /// Creates a schematic holding the contents of the clipboard.
+    pub fn to_schematic(&self) -> Result<Schematic, SchematicError> {
+        Schematic::from_voxels(self.size.as_uvec3().to_array(), self.voxels.iter().map(|&(offset, material)| (offset.as_uvec3().to_array(), material)))
+    }
+
+    /// Whether the clipboard holds no voxels.
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Gets the number of voxels along each axis of the copied box.
+    pub fn size(&self) -> IVec3 {
+        self.size
+    }
+
+    /// Gets the filled voxels of the box, as offsets from its minimum corner alongside their materials.
+    pub fn voxels(&self) -> &[(IVec3, u32)] {
+        &self.voxels
+    }
+
+    /// Reflects the contents of the clipboard along the given axis.
+    pub fn mirror(&mut self, axis: usize) {
+        for (offset, _) in &mut self.voxels {
+            offset[axis] = self.size[axis] - 1 - offset[axis];
+        }
+    }
+
+    /// Rotates the contents of the clipboard by a quarter turn around the given axis.
+    pub fn rotate(&mut self, axis: usize) {
+        let u = (axis + 1) % 3;
+        let v = (axis + 2) % 3;
+
+        for (offset, _) in &mut self.voxels {
+            let previous = *offset;
+            offset[u] = self.size[v] - 1 - previous[v];
+            offset[v] = previous[u];
+        }
+
+        let previous_size = self.size;
+        self.size[u] = previous_size[v];
+        self.size[v] = previous_size[u];
+    }
+
+    /// Gets the minimum corner at which the clipboard should be pasted so
+    /// that the center of its bottom face rests on the target voxel.
+    pub fn paste_origin(&self, target: IVec3) -> IVec3 {
+        target - ivec3(self.size.x / 2, 0, self.size.z / 2)
+    }
+
+    /// Gets the world-space voxels and materials produced by pasting the clipboard at the given minimum corner.
+    pub fn placed_at(&self, origin: IVec3) -> Vec<(IVec3, u32)> {
+        self.voxels.iter().map(|&(offset, material)| (origin + offset, material)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an asymmetric clipboard whose voxels are all distinguishable.
+    fn clipboard() -> Clipboard {
+        Clipboard::new(ivec3(2, 3, 4), vec![(IVec3::ZERO, 1), (ivec3(1, 0, 0), 2), (ivec3(0, 2, 1), 3), (ivec3(1, 1, 3), 4)])
+    }
+
+    #[test]
+    fn schematics_round_trip() {
+        let exported = clipboard().to_schematic().expect("Failed to export clipboard");
+        let imported = Clipboard::from_schematic(&exported);
+        assert_eq!(imported.size(), clipboard().size());
+
+        let mut voxels = imported.voxels().to_vec();
+        let mut expected = clipboard().voxels().to_vec();
+        voxels.sort_by_key(|&(x, _)| x.to_array());
+        expected.sort_by_key(|&(x, _)| x.to_array());
+        assert_eq!(voxels, expected);
+    }
+
+    #[test]
+    fn four_quarter_turns_are_the_identity() {
+        for axis in 0..3 {
+            let mut rotated = clipboard();
+            for _ in 0..4 {
+                rotated.rotate(axis);
+            }
+
+            assert_eq!(rotated.size(), clipboard().size());
+            assert_eq!(rotated.voxels(), clipboard().voxels());
+        }
+    }
+
+    #[test]
+    fn rotation_swaps_the_other_axes() {
+        let mut rotated = clipboard();
+        rotated.rotate(1);
+        assert_eq!(rotated.size(), ivec3(4, 3, 2));
+        assert!(rotated.voxels().iter().all(|&(x, _)| IVec3::ZERO.cmple(x).all() && x.cmplt(rotated.size()).all()));
+
+        rotated.rotate(0);
+        assert_eq!(rotated.size(), ivec3(4, 2, 3));
+    }
+
+    #[test]
+    fn mirroring_twice_is_the_identity() {
+        for axis in 0..3 {
+            let mut mirrored = clipboard();
+            mirrored.mirror(axis);
+            assert_ne!(mirrored.voxels(), clipboard().voxels());
+            assert_eq!(mirrored.size(), clipboard().size());
+
+            mirrored.mirror(axis);
+            assert_eq!(mirrored.voxels(), clipboard().voxels());
+        }
+    }
+
+    #[test]
+    fn paste_origin_centers_the_bottom_face() {
+        let clipboard = clipboard();
+        let origin = clipboard.paste_origin(ivec3(10, 5, -3));
+        assert_eq!(origin, ivec3(9, 5, -5));
+        assert_eq!(clipboard.placed_at(origin)[0], (origin, 1));
+        assert_eq!(Clipboard::default().paste_origin(IVec3::ONE), IVec3::ONE);
+    }
+}
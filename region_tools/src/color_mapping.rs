@@ -0,0 +1,98 @@
+use crate::clipboard::*;
+use magica_voxel::*;
+use serde::*;
+use voxel_engine::math::*;
+use voxel_std_types::palette::*;
+
+/// Decides which material each color of an imported `.vox` file becomes.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ColorMapping {
+    /// The materials that colors may map onto. When empty, every palette item is a candidate.
+    #[serde(default)]
+    materials: Vec<u32>,
+    /// The distance used to find the nearest palette color.
+    #[serde(default)]
+    metric: ColorMetric,
+    /// Palette indices of the `.vox` file that always map onto a specific material.
+    #[serde(default, rename = "override")]
+    overrides: Vec<ColorOverride>
+}
+
+impl ColorMapping {
+    /// The path of the file from which the mapping is loaded.
+    pub const CONFIG_PATH: &'static str = "config/vox_mapping.toml";
+
+    /// Loads the mapping from the configuration file. If no file exists, the default
+    /// mapping is returned. If the file is malformed, the default mapping is returned
+    /// alongside a description of the problem.
+    pub fn load() -> (Self, Option<String>) {
+        match std::fs::read_to_string(Self::CONFIG_PATH) {
+            Ok(text) => match toml::from_str(&text) {
+                Ok(mapping) => (mapping, None),
+                Err(error) => (Self::default(), Some(format!("Failed to parse {}: {error}", Self::CONFIG_PATH)))
+            },
+            Err(_) => (Self::default(), None)
+        }
+    }
+
+    /// Creates a clipboard from the scene of a `.vox` file. MagicaVoxel treats `z` as the
+    /// vertical axis, so the scene is turned upright while preserving its handedness.
+    pub fn import(&self, file: &VoxFile, entries: &[PaletteEntry]) -> Result<Clipboard, VoxError> {
+        let mut materials = [None; 256];
+        for (index, material) in materials.iter_mut().enumerate() {
+            *material = self.material_for(index as u8, file.color(index as u8), entries);
+        }
+
+        let voxels = file.scene_voxels()?.into_iter()
+            .filter_map(|([x, y, z], color)| materials[color as usize].map(|material| (ivec3(x, z, -y), material)))
+            .collect();
+
+        Ok(Clipboard::from_voxels(voxels))
+    }
+
+    /// Gets the material for a palette index of a `.vox` file with the given color.
+    fn material_for(&self, index: u8, color: [u8; 4], entries: &[PaletteEntry]) -> Option<u32> {
+        if let Some(entry) = self.overrides.iter().find(|x| x.color == index) {
+            return Some(entry.material);
+        }
+
+        entries.iter()
+            .filter(|x| self.materials.is_empty() || self.materials.contains(&x.material))
+            .min_by_key(|x| self.metric.distance(color, x.color))
+            .map(|x| x.material)
+    }
+}
+
+/// Measures the difference between two colors.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMetric {
+    /// Weights each channel according to how sensitive the eye is to it.
+    #[default]
+    Perceptual,
+    /// Weights each channel equally.
+    Rgb
+}
+
+impl ColorMetric {
+    /// Gets the squared distance between two colors, ignoring alpha.
+    fn distance(self, a: [u8; 4], b: [u8; 4]) -> u32 {
+        let [dr, dg, db] = [0, 1, 2].map(|x| (a[x] as i32 - b[x] as i32).pow(2) as u32);
+        match self {
+            Self::Perceptual => {
+                let mean_red = (a[0] as u32 + b[0] as u32) / 2;
+                (((512 + mean_red) * dr) >> 8) + 4 * dg + (((767 - mean_red) * db) >> 8)
+            },
+            Self::Rgb => dr + dg + db
+        }
+    }
+}
+
+/// Maps a palette index of a `.vox` file directly onto a material.
+#[derive(Copy, Clone, Debug, Deserialize)]
+struct ColorOverride {
+    /// The palette index in the `.vox` file, from `1` to `255`.
+    color: u8,
+    /// The material that voxels of the color become.
+    material: u32
+}
@@ -0,0 +1,570 @@
+use crate::clipboard::*;
+use crate::color_mapping::*;
+use crate::selection::*;
+use magica_voxel::*;
+use schematic::*;
+use voxel_engine::*;
+use voxel_engine::input::*;
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+use voxel_engine::player::*;
+use voxel_std_types::bindings::*;
+use voxel_std_types::editing::*;
+use voxel_std_types::overlay::*;
+use voxel_std_types::palette::*;
+use voxel_std_types::targeting::*;
+use wings::*;
+
+/// Holds copied voxels so that they may be transformed and pasted.
+mod clipboard;
+/// Converts MagicaVoxel models into materials from the palette.
+mod color_mapping;
+/// Tracks the box of voxels chosen by the player.
+mod selection;
+
+instantiate_systems!(Client, [RegionTools]);
+
+/// Allows the player to select a box of voxels, and then fill, replace, hollow, clear, copy, or export it. Since the engine does not
+/// expose the contents of voxels to mods, replacing, copying, and exporting only affect voxels whose materials are known to the `VoxelEditor`.
+/// Schematics and MagicaVoxel models may also be imported into the clipboard and pasted.
+#[export_system]
+pub struct RegionTools {
+    /// The voxels most recently copied or imported by the player.
+    clipboard: Clipboard,
+    /// The context handle.
+    ctx: WingsContextHandle<Self>,
+    /// A message describing the result of the last operation.
+    notice: Option<String>,
+    /// Whether the region window is shown. It starts open only if there are binding conflicts to report.
+    open: bool,
+    /// Whether a preview of the clipboard follows the pointer, waiting to be pasted.
+    paste_armed: bool,
+    /// Projects the selection outline onto the screen.
+    projection: ScreenProjection,
+    /// The palette index of the material to replace.
+    replace_from: usize,
+    /// The file to which schematics are exported, and from which they are imported.
+    schematic_path: String,
+    /// The box of voxels that is currently selected.
+    selection: Selection,
+    /// Holds handles for accessing user input.
+    user_actions: UserActions,
+    /// The MagicaVoxel file from which models are imported.
+    vox_path: String
+}
+
+impl RegionTools {
+    /// The maximum number of voxels that a single operation may affect.
+    const MAX_VOLUME: u64 = 1 << 18;
+
+    /// Applies an operation requested by the player.
+    fn apply(&mut self, operation: RegionOperation) {
+        if operation.uses_selection() && Self::MAX_VOLUME < self.selection.volume() {
+            self.notice = Some(format!("The selection is larger than {} voxels", Self::MAX_VOLUME));
+            return;
+        }
+
+        let palette = self.ctx.get::<dyn ItemPalette>();
+        let maybe_material = palette.selected_entry().map(|x| x.material);
+        let entries = palette.entries();
+        let maybe_replace_from = entries.get(self.replace_from).map(|x| x.material);
+        drop(palette);
+
+        let mut editor = self.ctx.get_mut::<dyn VoxelEditor>();
+        match operation {
+            RegionOperation::Clear => editor.delete_voxels(self.selection.voxels()),
+            RegionOperation::Copy => {
+                self.clipboard = Self::copy_selection(&self.selection, &*editor);
+                self.notice = Some(format!("Copied {} voxels with known materials", self.clipboard.voxels().len()));
+            },
+            RegionOperation::Export => {
+                self.clipboard = Self::copy_selection(&self.selection, &*editor);
+                self.notice = Some(match self.export_clipboard() {
+                    Ok(()) => format!("Exported {} voxels with known materials to {}", self.clipboard.voxels().len(), self.schematic_path),
+                    Err(error) => format!("Failed to export {}: {error}", self.schematic_path)
+                });
+            },
+            RegionOperation::Fill => if let Some(material) = maybe_material {
+                editor.place_voxels(self.selection.voxels(), material);
+            },
+            RegionOperation::Hollow => editor.delete_voxels(self.selection.interior()),
+            RegionOperation::Import => match self.import_clipboard() {
+                Ok(clipboard) => {
+                    self.notice = Some(format!("Imported {} voxels from {}", clipboard.voxels().len(), self.schematic_path));
+                    self.paste_armed = !clipboard.is_empty();
+                    self.clipboard = clipboard;
+                },
+                Err(error) => self.notice = Some(format!("Failed to import {}: {error}", self.schematic_path))
+            },
+            RegionOperation::ImportVox => match self.import_vox(&entries) {
+                Ok(clipboard) => {
+                    self.notice = Some(format!("Imported {} voxels from {}", clipboard.voxels().len(), self.vox_path));
+                    self.paste_armed = !clipboard.is_empty();
+                    self.clipboard = clipboard;
+                },
+                Err(error) => self.notice = Some(format!("Failed to import {}: {error}", self.vox_path))
+            },
+            RegionOperation::Mirror(axis) => self.clipboard.mirror(axis),
+            RegionOperation::Paste(origin) => {
+                editor.place_materials(self.clipboard.placed_at(origin));
+                self.paste_armed = false;
+            },
+            RegionOperation::Replace => if let (Some(from), Some(to)) = (maybe_replace_from, maybe_material) {
+                let voxels = self.selection.voxels();
+                let materials = editor.materials_at(voxels.clone());
+                let replaced = voxels.into_iter().zip(materials)
+                    .filter_map(|(voxel, material)| (material == Some(from)).then_some(voxel))
+                    .collect::<Vec<_>>();
+
+                self.notice = Some(format!("Replaced {} voxels with known materials", replaced.len()));
+                editor.place_voxels(replaced, to);
+            },
+            RegionOperation::Rotate(axis) => self.clipboard.rotate(axis)
+        }
+    }
+
+    /// Reads the known materials of every voxel in the selection into a new clipboard.
+    fn copy_selection(selection: &Selection, editor: &dyn VoxelEditor) -> Clipboard {
+        match selection.bounds() {
+            Some((min, max)) => {
+                let voxels = selection.voxels();
+                let materials = editor.materials_at(voxels.clone());
+                let copied = voxels.into_iter().zip(materials)
+                    .filter_map(|(voxel, material)| material.map(|x| (voxel - min, x)))
+                    .collect();
+
+                Clipboard::new(max - min + IVec3::ONE, copied)
+            },
+            None => Clipboard::default()
+        }
+    }
+
+    /// Writes the clipboard to the schematic file.
+    fn export_clipboard(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = std::path::Path::new(&self.schematic_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, self.clipboard.to_schematic()?.write())?;
+        Ok(())
+    }
+
+    /// Reads a clipboard from the schematic file.
+    fn import_clipboard(&self) -> Result<Clipboard, Box<dyn std::error::Error>> {
+        let bytes = std::fs::read(&self.schematic_path)?;
+        Ok(Clipboard::from_schematic(&Schematic::read(&bytes)?))
+    }
+
+    /// Reads a clipboard from the MagicaVoxel file, mapping its colors onto palette items.
+    fn import_vox(&self, entries: &[PaletteEntry]) -> Result<Clipboard, Box<dyn std::error::Error>> {
+        let (mapping, maybe_error) = ColorMapping::load();
+        if let Some(error) = maybe_error {
+            return Err(error.into());
+        }
+
+        let bytes = std::fs::read(&self.vox_path)?;
+        Ok(mapping.import(&VoxFile::read(&bytes)?, entries)?)
+    }
+
+    /// Draws the selection outline, the paste preview, and the region editing panel if it is open.
+    /// Returns the operation that the player requested, if any.
+    fn draw_gui(&mut self, transform: &Transform, pointer_direction: Option<Vec3A>, paste_origin: Option<IVec3>) -> Option<RegionOperation> {
+        let entries = self.ctx.get::<dyn ItemPalette>().entries();
+        let egui_system = self.ctx.get::<dyn egui::Egui>();
+        let egui_ctx = egui_system.context();
+        let painter = egui_ctx.layer_painter(egui::LayerId::background());
+
+        if let (Some(direction), Some(pointer_position)) = (pointer_direction, egui_ctx.input(|x| x.pointer.latest_pos())) {
+            self.projection.calibrate(transform, direction, pointer_position, painter.clip_rect());
+        }
+
+        for corner in [self.selection.first, self.selection.second].into_iter().flatten() {
+            let min = corner.as_vec3a();
+            self.projection.draw_box_outline(&painter, transform, min, min + Vec3A::ONE, egui::Stroke::new(1.5, egui::Color32::LIGHT_BLUE));
+        }
+
+        if let Some((min, max)) = self.selection.bounds() {
+            self.projection.draw_box_outline(&painter, transform, min.as_vec3a(), (max + IVec3::ONE).as_vec3a(), egui::Stroke::new(2.0, egui::Color32::YELLOW));
+        }
+
+        if let Some(origin) = paste_origin {
+            self.draw_paste_preview(&painter, transform, &entries, origin);
+        }
+
+        let mut operation = None;
+        let mut open = self.open;
+        egui::Window::new("Region").open(&mut open).resizable(false).show(&egui_ctx, |ui| {
+            match self.selection.size() {
+                Some(size) => ui.label(format!("Size: {} × {} × {} ({} voxels)", size.x, size.y, size.z, self.selection.volume())),
+                None => ui.label("Choose two corners to select a region.")
+            };
+
+            ui.add_enabled_ui(self.selection.bounds().is_some(), |ui| {
+                ui.horizontal(|ui| {
+                    for (name, kind) in [("Fill", RegionOperation::Fill), ("Hollow", RegionOperation::Hollow), ("Clear", RegionOperation::Clear), ("Copy", RegionOperation::Copy)] {
+                        if ui.button(name).clicked() {
+                            operation = Some(kind);
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let selected_text = entries.get(self.replace_from).map(|x| x.name.as_str()).unwrap_or_default();
+                    egui::ComboBox::from_id_source("replace_from").selected_text(selected_text).show_ui(ui, |ui| {
+                        for (index, entry) in entries.iter().enumerate() {
+                            ui.selectable_value(&mut self.replace_from, index, &entry.name);
+                        }
+                    });
+
+                    if ui.button("Replace with selected item").on_hover_text("Only voxels edited through the standard tools have a known material").clicked() {
+                        operation = Some(RegionOperation::Replace);
+                    }
+                });
+            });
+
+            ui.separator();
+
+            let size = self.clipboard.size();
+            ui.label(format!("Clipboard: {} × {} × {} ({} voxels)", size.x, size.y, size.z, self.clipboard.voxels().len()));
+
+            ui.add_enabled_ui(!self.clipboard.is_empty(), |ui| {
+                for (axis, axis_name) in ["X", "Y", "Z"].into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("Rotate around {axis_name}")).clicked() {
+                            operation = Some(RegionOperation::Rotate(axis));
+                        }
+
+                        if ui.button(format!("Mirror along {axis_name}")).clicked() {
+                            operation = Some(RegionOperation::Mirror(axis));
+                        }
+                    });
+                }
+
+                ui.checkbox(&mut self.paste_armed, "Show paste preview");
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Schematic");
+                ui.text_edit_singleline(&mut self.schematic_path);
+            });
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(self.selection.bounds().is_some(), egui::Button::new("Export selection")).clicked() {
+                    operation = Some(RegionOperation::Export);
+                }
+
+                if ui.button("Import").clicked() {
+                    operation = Some(RegionOperation::Import);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("MagicaVoxel model");
+                ui.text_edit_singleline(&mut self.vox_path);
+            });
+
+            if ui.button("Import model").on_hover_text(format!("Colors are matched to the nearest palette item, as configured by {}", ColorMapping::CONFIG_PATH)).clicked() {
+                operation = Some(RegionOperation::ImportVox);
+            }
+
+            if let Some(notice) = &self.notice {
+                ui.label(notice);
+            }
+        });
+
+        self.open = open;
+        operation
+    }
+
+    /// Draws the outline of the clipboard at the paste location, and the voxels that it would place.
+    fn draw_paste_preview(&self, painter: &egui::Painter, transform: &Transform, entries: &[PaletteEntry], origin: IVec3) {
+        /// The maximum number of individual voxels to draw in the preview.
+        const MAX_PREVIEW_VOXELS: usize = 512;
+        /// The opacity of the previewed voxels.
+        const PREVIEW_ALPHA: u8 = 96;
+
+        let min = origin.as_vec3a();
+        self.projection.draw_box_outline(painter, transform, min, min + self.clipboard.size().as_vec3a(), egui::Stroke::new(1.5, egui::Color32::WHITE));
+
+        if self.clipboard.voxels().len() <= MAX_PREVIEW_VOXELS {
+            for (voxel, material) in self.clipboard.placed_at(origin) {
+                let [r, g, b, _] = entries.iter().find(|x| x.material == material).map(|x| x.color).unwrap_or([255; 4]);
+                let voxel_min = voxel.as_vec3a();
+                self.projection.draw_box_faces(painter, transform, voxel_min, voxel_min + Vec3A::ONE, egui::Color32::from_rgba_unmultiplied(r, g, b, PREVIEW_ALPHA));
+            }
+        }
+    }
+
+    /// Updates the selection and applies operations according to the player's input.
+    fn handle_region_input(&mut self, _: &voxel_engine::timing::on::Frame) {
+        /// The maximum distance away that the user may target a voxel.
+        const MAX_SELECTION_DISTANCE: f32 = 256.0;
+
+        let input = self.ctx.get::<dyn Input>();
+        let clear_selection = input.get(self.user_actions.clear_selection);
+        let copy_selection = input.get(self.user_actions.copy_selection);
+        let paste = input.get(self.user_actions.paste);
+        let rotate_clipboard = input.get(self.user_actions.rotate_clipboard);
+        let select_first_corner = input.get(self.user_actions.select_first_corner);
+        let select_second_corner = input.get(self.user_actions.select_second_corner);
+        let toggle_region_window = input.get(self.user_actions.toggle_region_window);
+        let maybe_pointer_direction = input.pointer_direction();
+        drop(input);
+
+        let transform = self.ctx.get::<dyn Player>().get_transform();
+        let mut paste_origin = None;
+        let mut operation = None;
+
+        if let Some(direction) = maybe_pointer_direction {
+            let pointer_ray = Ray {
+                position: transform.position,
+                direction,
+                max_distance: MAX_SELECTION_DISTANCE
+            };
+
+            if select_first_corner.pressed || select_second_corner.pressed || self.paste_armed || paste.pressed {
+                if let Some(hit) = self.ctx.get::<dyn Raycaster>().cast_world(&pointer_ray) {
+                    if select_first_corner.pressed {
+                        self.selection.first = Some(hit.voxel);
+                    }
+                    else if select_second_corner.pressed {
+                        self.selection.second = Some(hit.voxel);
+                    }
+
+                    let origin = self.clipboard.paste_origin(placement_voxel(&pointer_ray, &hit));
+                    if self.paste_armed && paste.pressed {
+                        operation = Some(RegionOperation::Paste(origin));
+                    }
+                    else if self.paste_armed {
+                        paste_origin = Some(origin);
+                    }
+                }
+            }
+        }
+
+        if clear_selection.pressed {
+            self.selection = Selection::default();
+            self.notice = None;
+            self.paste_armed = false;
+        }
+
+        self.open ^= toggle_region_window.pressed;
+
+        if copy_selection.pressed {
+            operation = Some(RegionOperation::Copy);
+        }
+        else if rotate_clipboard.pressed {
+            operation = Some(RegionOperation::Rotate(1));
+        }
+        else if paste.pressed && !self.paste_armed {
+            self.paste_armed = !self.clipboard.is_empty();
+        }
+
+        let gui_operation = self.draw_gui(&transform, maybe_pointer_direction, paste_origin);
+        if let Some(operation) = operation.or(gui_operation) {
+            self.apply(operation);
+        }
+    }
+
+    /// Registers the set of actions relevant to region selection, with the player's overrides applied.
+    /// Returns the actions, alongside their bindings so that conflicts may be reported and the actions listed for rebinding.
+    fn get_user_actions(ctx: &mut WingsContextHandle<Self>) -> (UserActions, ActionBindings) {
+        let mut input = ctx.get_mut::<dyn Input>();
+        let mut bindings = ActionBindings::new("Region tools");
+
+        let clear_selection = bindings.define_digital::<Self>(
+            &mut *input,
+            "Clear selection",
+            "Deselects the currently-selected region.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Escape)
+                },
+            ]
+        );
+
+        let copy_selection = bindings.define_digital::<Self>(
+            &mut *input,
+            "Copy selection",
+            "Copies the voxels of the selected region to the clipboard.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::C)
+                },
+            ]
+        );
+
+        let paste = bindings.define_digital::<Self>(
+            &mut *input,
+            "Paste",
+            "Shows a preview of the clipboard where the player's pointer is, and pastes it when pressed again.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::V)
+                },
+            ]
+        );
+
+        let rotate_clipboard = bindings.define_digital::<Self>(
+            &mut *input,
+            "Rotate clipboard",
+            "Rotates the clipboard by a quarter turn around the vertical axis.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::R)
+                },
+            ]
+        );
+
+        let select_first_corner = bindings.define_digital::<Self>(
+            &mut *input,
+            "Select first corner",
+            "Sets the first corner of the selected region to the voxel where the player's pointer is.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Q)
+                },
+            ]
+        );
+
+        let select_second_corner = bindings.define_digital::<Self>(
+            &mut *input,
+            "Select second corner",
+            "Sets the second corner of the selected region to the voxel where the player's pointer is.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::E)
+                },
+            ]
+        );
+
+        let toggle_region_window = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle region window",
+            "Shows or hides the window for editing the selected region and the clipboard.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::F4)
+                },
+            ]
+        );
+
+        let actions = UserActions {
+            clear_selection,
+            copy_selection,
+            paste,
+            rotate_clipboard,
+            select_first_corner,
+            select_second_corner,
+            toggle_region_window
+        };
+
+        (actions, bindings)
+    }
+}
+
+impl WingsSystem for RegionTools {
+    const DEPENDENCIES: Dependencies = dependencies()
+        .with::<dyn ActionRegistry>()
+        .with::<dyn egui::Egui>()
+        .with::<dyn Input>()
+        .with::<dyn ItemPalette>()
+        .with::<dyn Player>()
+        .with::<dyn Raycaster>()
+        .with::<dyn VoxelEditor>();
+
+    const EVENT_HANDLERS: EventHandlers<Self> = event_handlers()
+        .with(Self::handle_region_input);
+
+    fn new(mut ctx: WingsContextHandle<Self>) -> Self {
+        let (user_actions, bindings) = Self::get_user_actions(&mut ctx);
+        ctx.get_mut::<dyn ActionRegistry>().register_actions(bindings.actions());
+
+        let clipboard = Clipboard::default();
+        let notice = Some(bindings.warnings().join("\n")).filter(|x| !x.is_empty());
+        let open = notice.is_some();
+        let paste_armed = false;
+        let projection = ScreenProjection::load();
+        let replace_from = 0;
+        let schematic_path = "schematics/structure.vxs".to_string();
+        let selection = Selection::default();
+        let vox_path = "models/import.vox".to_string();
+
+        Self {
+            clipboard,
+            ctx,
+            notice,
+            open,
+            paste_armed,
+            projection,
+            replace_from,
+            schematic_path,
+            selection,
+            user_actions,
+            vox_path
+        }
+    }
+}
+
+/// An edit requested by the player.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RegionOperation {
+    /// Empties every voxel in the selection.
+    Clear,
+    /// Copies the voxels of the selection to the clipboard.
+    Copy,
+    /// Copies the voxels of the selection to the clipboard, and saves them as a schematic.
+    Export,
+    /// Fills every voxel in the selection with the selected item.
+    Fill,
+    /// Empties every voxel in the selection that does not lie on the surface of the box.
+    Hollow,
+    /// Loads a schematic into the clipboard, and shows a paste preview.
+    Import,
+    /// Loads a MagicaVoxel model into the clipboard, and shows a paste preview.
+    ImportVox,
+    /// Reflects the clipboard along the given axis.
+    Mirror(usize),
+    /// Places the clipboard with its minimum corner at the given voxel.
+    Paste(IVec3),
+    /// Changes voxels of one material in the selection into the selected item.
+    Replace,
+    /// Rotates the clipboard by a quarter turn around the given axis.
+    Rotate(usize)
+}
+
+impl RegionOperation {
+    /// Whether this operation reads or writes every voxel of the selection.
+    fn uses_selection(self) -> bool {
+        matches!(self, Self::Clear | Self::Copy | Self::Export | Self::Fill | Self::Hollow | Self::Replace)
+    }
+}
+
+/// Holds the set of actions relevant to user input.
+#[derive(Copy, Clone, Debug)]
+struct UserActions {
+    /// Deselects the currently-selected region.
+    pub clear_selection: ActionId<Digital>,
+    /// Copies the voxels of the selected region to the clipboard.
+    pub copy_selection: ActionId<Digital>,
+    /// Shows a preview of the clipboard, and pastes it when pressed again.
+    pub paste: ActionId<Digital>,
+    /// Rotates the clipboard around the vertical axis.
+    pub rotate_clipboard: ActionId<Digital>,
+    /// Sets the first corner of the selected region.
+    pub select_first_corner: ActionId<Digital>,
+    /// Sets the second corner of the selected region.
+    pub select_second_corner: ActionId<Digital>,
+    /// Shows or hides the region window.
+    pub toggle_region_window: ActionId<Digital>
+}
@@ -0,0 +1,114 @@
+use voxel_engine::math::*;
+
+/// A box of voxels spanned by two corners chosen by the player.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Selection {
+    /// The first corner of the box.
+    pub first: Option<IVec3>,
+    /// The second corner of the box.
+    pub second: Option<IVec3>
+}
+
+impl Selection {
+    /// Gets the minimum and maximum voxels of the box, inclusive,
+    /// if both corners have been chosen.
+    pub fn bounds(&self) -> Option<(IVec3, IVec3)> {
+        let first = self.first?;
+        let second = self.second?;
+        Some((first.min(second), first.max(second)))
+    }
+
+    /// Gets the number of voxels along each axis of the box.
+    pub fn size(&self) -> Option<IVec3> {
+        self.bounds().map(|(min, max)| max - min + IVec3::ONE)
+    }
+
+    /// Gets the total number of voxels within the box.
+    pub fn volume(&self) -> u64 {
+        self.size().map(|x| x.x as u64 * x.y as u64 * x.z as u64).unwrap_or(0)
+    }
+
+    /// Gets every voxel within the box.
+    pub fn voxels(&self) -> Vec<IVec3> {
+        self.voxels_where(|_| true)
+    }
+
+    /// Gets every voxel within the box that does not lie on its surface.
+    pub fn interior(&self) -> Vec<IVec3> {
+        match self.bounds() {
+            Some((min, max)) => self.voxels_where(|x| min.cmplt(x).all() && x.cmplt(max).all()),
+            None => Vec::new()
+        }
+    }
+
+    /// Gets every voxel within the box that satisfies the given predicate.
+    fn voxels_where(&self, mut predicate: impl FnMut(IVec3) -> bool) -> Vec<IVec3> {
+        let mut result = Vec::new();
+
+        if let Some((min, max)) = self.bounds() {
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    for z in min.z..=max.z {
+                        let voxel = ivec3(x, y, z);
+                        if predicate(voxel) {
+                            result.push(voxel);
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a selection between the given corners.
+    fn selection(first: IVec3, second: IVec3) -> Selection {
+        Selection {
+            first: Some(first),
+            second: Some(second)
+        }
+    }
+
+    #[test]
+    fn bounds_order_the_corners() {
+        let selection = selection(ivec3(4, -1, 2), ivec3(1, 3, -5));
+        assert_eq!(selection.bounds(), Some((ivec3(1, -1, -5), ivec3(4, 3, 2))));
+        assert_eq!(selection.size(), Some(ivec3(4, 5, 8)));
+    }
+
+    #[test]
+    fn incomplete_selections_are_empty() {
+        let selection = Selection { first: Some(IVec3::ONE), second: None };
+        assert_eq!(selection.bounds(), None);
+        assert_eq!(selection.volume(), 0);
+        assert!(selection.voxels().is_empty());
+        assert!(selection.interior().is_empty());
+    }
+
+    #[test]
+    fn volume_counts_every_voxel() {
+        let selection = selection(ivec3(2, 2, 2), ivec3(-1, 0, 5));
+        assert_eq!(selection.volume(), 4 * 3 * 4);
+        assert_eq!(selection.voxels().len() as u64, selection.volume());
+        assert_eq!(Selection::default().volume(), 0);
+    }
+
+    #[test]
+    fn volume_does_not_overflow() {
+        assert_eq!(selection(IVec3::ZERO, ivec3(99_999, 99_999, 99_999)).volume(), 100_000u64.pow(3));
+    }
+
+    #[test]
+    fn interior_excludes_the_surface() {
+        let selection = selection(IVec3::ZERO, ivec3(3, 4, 5));
+        let interior = selection.interior();
+        assert_eq!(interior.len(), 2 * 3 * 4);
+        assert!(interior.iter().all(|x| IVec3::ZERO.cmplt(*x).all() && x.cmplt(ivec3(3, 4, 5)).all()));
+        assert!(self::selection(IVec3::ZERO, ivec3(1, 5, 5)).interior().is_empty());
+    }
+}
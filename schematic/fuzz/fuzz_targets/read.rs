@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::*;
+use schematic::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(schematic) = Schematic::read(data) {
+        let written = schematic.write();
+        assert_eq!(Schematic::read(&written).as_ref(), Ok(&schematic), "Schematic did not survive a round trip.");
+    }
+});
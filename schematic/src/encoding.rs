@@ -0,0 +1,176 @@
+use crate::*;
+use std::collections::*;
+
+/// The bytes at the start of every schematic.
+const SIGNATURE: [u8; 4] = *b"VXSC";
+/// The version of the format produced by the writer.
+const VERSION: u16 = 1;
+
+/// Produces the binary representation of a schematic. The layout is:
+///
+/// - the signature `VXSC`
+/// - the format version, as a little-endian `u16`
+/// - the size along each axis, as three varints
+/// - the number of palette entries, followed by each material ID, as varints
+/// - runs of identical voxels, each a varint length followed by a varint palette index,
+///   where index zero is empty and index `n` refers to palette entry `n - 1`
+///
+/// All varints are unsigned LEB128.
+pub fn encode(schematic: &Schematic) -> Vec<u8> {
+    let mut palette = Vec::new();
+    let mut palette_indices = HashMap::new();
+    let mut runs = Vec::<(u64, u64)>::new();
+
+    for &voxel in &schematic.voxels {
+        let index = match voxel {
+            Some(material) => *palette_indices.entry(material).or_insert_with(|| {
+                palette.push(material);
+                palette.len() as u64
+            }),
+            None => 0
+        };
+
+        match runs.last_mut() {
+            Some((length, last_index)) if *last_index == index => *length += 1,
+            _ => runs.push((1, index))
+        }
+    }
+
+    let mut result = Vec::new();
+    result.extend_from_slice(&SIGNATURE);
+    result.extend_from_slice(&VERSION.to_le_bytes());
+
+    for axis in schematic.size {
+        write_varint(&mut result, axis as u64);
+    }
+
+    write_varint(&mut result, palette.len() as u64);
+    for material in palette {
+        write_varint(&mut result, material as u64);
+    }
+
+    for (length, index) in runs {
+        write_varint(&mut result, length);
+        write_varint(&mut result, index);
+    }
+
+    result
+}
+
+/// Reads a schematic from its binary representation, rejecting malformed data.
+pub fn decode(bytes: &[u8]) -> Result<Schematic, SchematicError> {
+    let mut reader = ByteReader { bytes };
+
+    if reader.read_bytes(SIGNATURE.len())? != SIGNATURE {
+        return Err(SchematicError::InvalidSignature);
+    }
+
+    let version = u16::from_le_bytes([reader.read_byte()?, reader.read_byte()?]);
+    if version != VERSION {
+        return Err(SchematicError::UnsupportedVersion(version));
+    }
+
+    let mut size = [0; 3];
+    for axis in &mut size {
+        let value = reader.read_varint()?;
+        *axis = u32::try_from(value).map_err(|_| SchematicError::TooLarge(value))?;
+    }
+
+    let mut schematic = Schematic::new(size)?;
+    let volume = schematic.voxels.len() as u64;
+
+    let palette_length = reader.read_varint()?;
+    if reader.bytes.len() < palette_length as usize {
+        return Err(SchematicError::UnexpectedEnd);
+    }
+
+    let palette = (0..palette_length)
+        .map(|_| reader.read_varint().and_then(|x| u32::try_from(x).map_err(|_| SchematicError::InvalidVarint)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut position = 0;
+    while position < volume {
+        let length = reader.read_varint()?;
+        let index = reader.read_varint()?;
+
+        if length == 0 || volume - position < length {
+            return Err(SchematicError::InvalidRunLength);
+        }
+
+        let material = match index {
+            0 => None,
+            _ => Some(*palette.get(index as usize - 1).ok_or(SchematicError::InvalidPaletteIndex(index))?)
+        };
+
+        schematic.voxels[position as usize..(position + length) as usize].fill(material);
+        position += length;
+    }
+
+    if reader.bytes.is_empty() {
+        Ok(schematic)
+    }
+    else {
+        Err(SchematicError::TrailingData)
+    }
+}
+
+/// Appends an unsigned LEB128 integer to the output.
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while 0x80 <= value {
+        output.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+
+    output.push(value as u8);
+}
+
+/// Consumes data from the front of a byte slice.
+struct ByteReader<'a> {
+    /// The bytes that have not yet been read.
+    bytes: &'a [u8]
+}
+
+impl<'a> ByteReader<'a> {
+    /// The maximum number of bytes in an encoded `u64`.
+    const MAX_VARINT_LENGTH: u32 = 10;
+
+    /// Reads a single byte.
+    fn read_byte(&mut self) -> Result<u8, SchematicError> {
+        let (&first, rest) = self.bytes.split_first().ok_or(SchematicError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    /// Reads the given number of bytes.
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], SchematicError> {
+        if self.bytes.len() < length {
+            return Err(SchematicError::UnexpectedEnd);
+        }
+
+        let (result, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    fn read_varint(&mut self) -> Result<u64, SchematicError> {
+        let mut result = 0u64;
+
+        for index in 0..Self::MAX_VARINT_LENGTH {
+            let byte = self.read_byte()?;
+            let bits = (byte & 0x7F) as u64;
+            let shift = 7 * index;
+
+            if (bits << shift) >> shift != bits {
+                return Err(SchematicError::InvalidVarint);
+            }
+
+            result |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        Err(SchematicError::InvalidVarint)
+    }
+}
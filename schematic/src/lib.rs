@@ -0,0 +1,192 @@
+use crate::encoding::*;
+use std::fmt;
+
+/// Reads and writes the binary representation of schematics.
+mod encoding;
+
+/// A box of voxels that may be saved to disk and loaded again.
+/// Each voxel is either empty or filled with a material ID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schematic {
+    /// The number of voxels along each axis.
+    size: [u32; 3],
+    /// The contents of each voxel, with the `x` coordinate varying fastest and `z` slowest.
+    voxels: Vec<Option<u32>>
+}
+
+impl Schematic {
+    /// The largest number of voxels that a schematic may contain.
+    pub const MAX_VOLUME: u64 = 1 << 22;
+
+    /// Creates an empty schematic of the given size.
+    pub fn new(size: [u32; 3]) -> Result<Self, SchematicError> {
+        let volume = size.iter().try_fold(1u64, |volume, &x| volume.checked_mul(x as u64)).unwrap_or(u64::MAX);
+        if Self::MAX_VOLUME < volume {
+            return Err(SchematicError::TooLarge(volume));
+        }
+
+        Ok(Self {
+            size,
+            voxels: vec![None; volume as usize]
+        })
+    }
+
+    /// Creates a schematic of the given size, filled with the provided voxels.
+    /// Voxels that lie outside of the schematic are ignored.
+    pub fn from_voxels(size: [u32; 3], voxels: impl IntoIterator<Item = ([u32; 3], u32)>) -> Result<Self, SchematicError> {
+        let mut result = Self::new(size)?;
+        for (position, material) in voxels {
+            result.set(position, Some(material));
+        }
+        Ok(result)
+    }
+
+    /// Loads a schematic from its binary representation.
+    pub fn read(bytes: &[u8]) -> Result<Self, SchematicError> {
+        decode(bytes)
+    }
+
+    /// Produces the binary representation of this schematic.
+    pub fn write(&self) -> Vec<u8> {
+        encode(self)
+    }
+
+    /// Gets the contents of the voxel at the given position. Positions
+    /// outside of the schematic are empty.
+    pub fn get(&self, position: [u32; 3]) -> Option<u32> {
+        self.index(position).and_then(|x| self.voxels[x])
+    }
+
+    /// Sets the contents of the voxel at the given position. Positions
+    /// outside of the schematic are ignored.
+    pub fn set(&mut self, position: [u32; 3], material: Option<u32>) {
+        if let Some(index) = self.index(position) {
+            self.voxels[index] = material;
+        }
+    }
+
+    /// Gets the number of voxels along each axis.
+    pub fn size(&self) -> [u32; 3] {
+        self.size
+    }
+
+    /// Iterates over the positions and materials of all filled voxels.
+    pub fn filled_voxels(&self) -> impl '_ + Iterator<Item = ([u32; 3], u32)> {
+        self.voxels.iter().enumerate().filter_map(|(index, &voxel)| voxel.map(|material| (self.position(index), material)))
+    }
+
+    /// Gets the index of the given position in the voxel list, if it lies within the schematic.
+    fn index(&self, position: [u32; 3]) -> Option<usize> {
+        let [x, y, z] = position;
+        let [size_x, size_y, size_z] = self.size;
+        (x < size_x && y < size_y && z < size_z).then(|| x as usize + size_x as usize * (y as usize + size_y as usize * z as usize))
+    }
+
+    /// Gets the position that corresponds to the given index in the voxel list.
+    fn position(&self, index: usize) -> [u32; 3] {
+        let [size_x, size_y, _] = self.size.map(|x| x as usize);
+        [(index % size_x) as u32, (index / size_x % size_y) as u32, (index / (size_x * size_y)) as u32]
+    }
+}
+
+/// Describes why a schematic could not be created or read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchematicError {
+    /// The data does not begin with the schematic file signature.
+    InvalidSignature,
+    /// A voxel referenced a palette entry that does not exist.
+    InvalidPaletteIndex(u64),
+    /// The runs of voxels did not cover the schematic exactly.
+    InvalidRunLength,
+    /// A variable-length integer was longer than permitted.
+    InvalidVarint,
+    /// The data ended before the schematic was complete.
+    UnexpectedEnd,
+    /// The data continued after the schematic was complete.
+    TrailingData,
+    /// The schematic contains more voxels than permitted.
+    TooLarge(u64),
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u16)
+}
+
+impl fmt::Display for SchematicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature => f.write_str("not a schematic file"),
+            Self::InvalidPaletteIndex(index) => write!(f, "palette index {index} is out of range"),
+            Self::InvalidRunLength => f.write_str("voxel runs do not match the schematic size"),
+            Self::InvalidVarint => f.write_str("malformed variable-length integer"),
+            Self::UnexpectedEnd => f.write_str("unexpected end of data"),
+            Self::TrailingData => f.write_str("unexpected data after the end of the schematic"),
+            Self::TooLarge(volume) => write!(f, "schematic volume {volume} exceeds the maximum of {}", Schematic::MAX_VOLUME),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported schematic version {version}")
+        }
+    }
+}
+
+impl std::error::Error for SchematicError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the header of a schematic file with the given size and no palette.
+    fn header(size: [u64; 3]) -> Vec<u8> {
+        let mut result = b"VXSC".to_vec();
+        result.extend_from_slice(&1u16.to_le_bytes());
+        for mut axis in size {
+            while 0x80 <= axis {
+                result.push((axis as u8 & 0x7F) | 0x80);
+                axis >>= 7;
+            }
+
+            result.push(axis as u8);
+        }
+
+        result
+    }
+
+    #[test]
+    fn round_trip_preserves_voxels() {
+        let schematic = Schematic::from_voxels([3, 2, 4], [([0, 0, 0], 7), ([2, 1, 3], 9), ([1, 1, 1], 7)]).unwrap();
+        let read = Schematic::read(&schematic.write()).unwrap();
+        assert_eq!(read, schematic);
+        assert_eq!(read.get([2, 1, 3]), Some(9));
+        assert_eq!(read.get([1, 0, 0]), None);
+    }
+
+    #[test]
+    fn round_trip_preserves_empty_schematic() {
+        let schematic = Schematic::new([0, 5, 5]).unwrap();
+        assert_eq!(Schematic::read(&schematic.write()), Ok(schematic));
+    }
+
+    #[test]
+    fn overflowing_size_is_too_large() {
+        assert_eq!(Schematic::new([u32::MAX; 3]), Err(SchematicError::TooLarge(u64::MAX)));
+        assert_eq!(Schematic::read(&header([1 << 22, 1 << 21, 1 << 21])), Err(SchematicError::TooLarge(u64::MAX)));
+    }
+
+    #[test]
+    fn oversized_header_is_rejected() {
+        let volume = Schematic::MAX_VOLUME * 2;
+        assert_eq!(Schematic::read(&header([volume, 1, 1])), Err(SchematicError::TooLarge(volume)));
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        let bytes = Schematic::from_voxels([2, 2, 2], [([1, 1, 1], 3)]).unwrap().write();
+        for length in 0..bytes.len() {
+            assert!(Schematic::read(&bytes[..length]).is_err(), "Truncation to {length} bytes was accepted.");
+        }
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        assert_eq!(Schematic::read(b"not a schematic"), Err(SchematicError::InvalidSignature));
+        let mut bytes = Schematic::new([1, 1, 1]).unwrap().write();
+        bytes.push(0);
+        assert_eq!(Schematic::read(&bytes), Err(SchematicError::TrailingData));
+    }
+}
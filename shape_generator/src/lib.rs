@@ -0,0 +1,382 @@
+use crate::shapes::*;
+use voxel_engine::*;
+use voxel_engine::input::*;
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+use voxel_engine::player::*;
+use voxel_engine::timing::*;
+use voxel_std_types::bindings::*;
+use voxel_std_types::editing::*;
+use voxel_std_types::overlay::*;
+use voxel_std_types::palette::*;
+use voxel_std_types::targeting::*;
+use wings::*;
+
+/// Produces the voxels of parametrized shapes.
+mod shapes;
+
+instantiate_systems!(Client, [ShapeGenerator]);
+
+/// Allows the player to build lines, walls, arches, staircases, domes, and ellipsoids
+/// from one or two picked points, with a preview of the result.
+#[export_system]
+pub struct ShapeGenerator {
+    /// The context handle.
+    ctx: WingsContextHandle<Self>,
+    /// The second point of the shape, if it has been picked.
+    end: Option<IVec3>,
+    /// A message describing the result of the last generation.
+    notice: Option<String>,
+    /// Whether the shapes window is shown. It starts open only if there are binding conflicts to report.
+    open: bool,
+    /// The parameters of the shape to generate.
+    parameters: ShapeParameters,
+    /// The most recently generated shape, which is reused while its inputs remain unchanged.
+    preview: Option<ShapePreview>,
+    /// The number of seconds since the preview was last generated.
+    preview_age: f32,
+    /// Projects the preview onto the screen.
+    projection: ScreenProjection,
+    /// The first point of the shape, if it has been picked.
+    start: Option<IVec3>,
+    /// Holds handles for accessing user input.
+    user_actions: UserActions
+}
+
+impl ShapeGenerator {
+    /// Generates the shape for the given points, unless it is unchanged since the last generation.
+    /// While the end point follows the pointer, the shape is regenerated at most once per interval.
+    fn update_preview(&mut self, start: IVec3, end: IVec3, follows_pointer: bool) {
+        /// The minimum number of seconds between generations of a shape whose end point follows the pointer.
+        const POINTER_PREVIEW_INTERVAL: f32 = 0.1;
+
+        let key = (self.parameters, start, end);
+        let throttled = follows_pointer && self.preview_age < POINTER_PREVIEW_INTERVAL
+            && matches!(&self.preview, Some(x) if x.key.0 == key.0 && x.key.1 == key.1);
+
+        if !throttled && !matches!(&self.preview, Some(x) if x.key == key) {
+            self.preview = Some(ShapePreview {
+                key,
+                voxels: self.parameters.voxels(start, end)
+            });
+            self.preview_age = 0.0;
+        }
+    }
+
+    /// Places the shape spanned by the picked points, using the selected item.
+    fn generate(&mut self, start: IVec3, end: IVec3) {
+        let maybe_material = self.ctx.get::<dyn ItemPalette>().selected_entry().map(|x| x.material);
+        let Some(material) = maybe_material else {
+            self.notice = Some("Select an item to build with".to_string());
+            return;
+        };
+
+        self.update_preview(start, end, false);
+        match self.preview.as_ref().and_then(|x| x.voxels.clone()) {
+            Some(voxels) => {
+                self.notice = Some(format!("Placed {} voxels", voxels.len()));
+                self.ctx.get_mut::<dyn VoxelEditor>().place_voxels(voxels, material);
+                self.start = None;
+                self.end = None;
+            },
+            None => self.notice = Some(format!("The shape is larger than {} voxels", ShapeParameters::MAX_VOXELS))
+        }
+    }
+
+    /// Draws the preview of the shape and the parameter panel if it is open.
+    /// Returns whether the player requested that the shape be generated.
+    fn draw_gui(&mut self, transform: &Transform, pointer_direction: Option<Vec3A>, span: Option<(IVec3, IVec3)>) -> bool {
+        if let Some((start, end)) = span {
+            let follows_pointer = self.parameters.kind.uses_end_point() && self.end.is_none();
+            self.update_preview(start, end, follows_pointer);
+        }
+
+        let selected_entry = self.ctx.get::<dyn ItemPalette>().selected_entry();
+        let egui_system = self.ctx.get::<dyn egui::Egui>();
+        let egui_ctx = egui_system.context();
+        let painter = egui_ctx.layer_painter(egui::LayerId::background());
+
+        if let (Some(direction), Some(pointer_position)) = (pointer_direction, egui_ctx.input(|x| x.pointer.latest_pos())) {
+            self.projection.calibrate(transform, direction, pointer_position, painter.clip_rect());
+        }
+
+        for point in [self.start, self.end].into_iter().flatten() {
+            let min = point.as_vec3a();
+            self.projection.draw_box_outline(&painter, transform, min, min + Vec3A::ONE, egui::Stroke::new(1.5, egui::Color32::LIGHT_GREEN));
+        }
+
+        let preview = span.and(self.preview.as_ref()).map(|x| x.voxels.as_deref());
+        let too_large = matches!(preview, Some(None));
+        if let Some(Some(voxels)) = preview {
+            let [r, g, b, _] = selected_entry.map(|x| x.color).unwrap_or([255; 4]);
+            self.draw_preview(&painter, transform, voxels, egui::Color32::from_rgb(r, g, b));
+        }
+
+        let mut generate = false;
+        let mut open = self.open;
+        egui::Window::new("Shapes").open(&mut open).resizable(false).show(&egui_ctx, |ui| {
+            let parameters = &mut self.parameters;
+
+            egui::ComboBox::from_label("Shape").selected_text(parameters.kind.name()).show_ui(ui, |ui| {
+                for kind in ShapeKind::ALL {
+                    ui.selectable_value(&mut parameters.kind, kind, kind.name());
+                }
+            });
+
+            if parameters.kind.uses_height() {
+                ui.add(egui::Slider::new(&mut parameters.height, 1..=64).text("Height"));
+            }
+
+            if parameters.kind.uses_radius() {
+                ui.add(egui::Slider::new(&mut parameters.radius, 1..=32).text("Radius"));
+            }
+
+            if parameters.kind == ShapeKind::SpiralStairs {
+                ui.add(egui::Slider::new(&mut parameters.steps_per_turn, 4..=32).text("Steps per turn"));
+            }
+
+            if matches!(parameters.kind, ShapeKind::Dome | ShapeKind::Ellipsoid) {
+                ui.checkbox(&mut parameters.hollow, "Hollow");
+            }
+
+            if parameters.kind.uses_thickness() {
+                ui.add(egui::Slider::new(&mut parameters.thickness, 1..=8).text("Thickness"));
+            }
+
+            ui.separator();
+
+            let describe = |point: Option<IVec3>| point.map(|x| format!("{}, {}, {}", x.x, x.y, x.z)).unwrap_or_else(|| "not picked".to_string());
+            ui.label(format!("Start: {}", describe(self.start)));
+            if parameters.kind.uses_end_point() {
+                ui.label(format!("End: {}", describe(self.end)));
+            }
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(self.start.is_some() && !too_large, egui::Button::new("Generate")).clicked() {
+                    generate = true;
+                }
+
+                if ui.button("Clear points").clicked() {
+                    self.start = None;
+                    self.end = None;
+                    self.notice = None;
+                }
+            });
+
+            if too_large {
+                ui.label(format!("The shape is larger than {} voxels", ShapeParameters::MAX_VOXELS));
+            }
+            else if let Some(notice) = &self.notice {
+                ui.label(notice);
+            }
+        });
+
+        self.open = open;
+        generate
+    }
+
+    /// Draws the voxels that a shape would place, or only its bounds if it has too many voxels.
+    fn draw_preview(&self, painter: &egui::Painter, transform: &Transform, voxels: &[IVec3], color: egui::Color32) {
+        /// The maximum number of individual voxels to draw in the preview.
+        const MAX_PREVIEW_VOXELS: usize = 1024;
+        /// The opacity of the previewed voxels.
+        const PREVIEW_ALPHA: u8 = 96;
+
+        let min = voxels.iter().copied().reduce(IVec3::min).unwrap_or_default();
+        let max = voxels.iter().copied().reduce(IVec3::max).unwrap_or_default();
+        self.projection.draw_box_outline(painter, transform, min.as_vec3a(), (max + IVec3::ONE).as_vec3a(), egui::Stroke::new(1.0, egui::Color32::WHITE));
+
+        if voxels.len() <= MAX_PREVIEW_VOXELS {
+            let fill = egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), PREVIEW_ALPHA);
+            for voxel in voxels {
+                let voxel_min = voxel.as_vec3a();
+                self.projection.draw_box_faces(painter, transform, voxel_min, voxel_min + Vec3A::ONE, fill);
+            }
+        }
+    }
+
+    /// Updates the picked points, previews the shape, and generates it according to the player's input.
+    fn handle_shape_input(&mut self, _: &voxel_engine::timing::on::Frame) {
+        /// The maximum distance away that the user may target a voxel.
+        const MAX_PICK_DISTANCE: f32 = 256.0;
+
+        let input = self.ctx.get::<dyn Input>();
+        let generate_shape = input.get(self.user_actions.generate_shape);
+        let pick_end = input.get(self.user_actions.pick_end);
+        let pick_start = input.get(self.user_actions.pick_start);
+        let toggle_shapes_window = input.get(self.user_actions.toggle_shapes_window);
+        let maybe_pointer_direction = input.pointer_direction();
+        drop(input);
+
+        self.open ^= toggle_shapes_window.pressed;
+
+        self.preview_age += self.ctx.get::<dyn FrameTiming>().frame_duration().as_secs_f32();
+
+        let transform = self.ctx.get::<dyn Player>().get_transform();
+        let mut pointer_target = None;
+
+        if let Some(direction) = maybe_pointer_direction {
+            let pointer_ray = Ray {
+                position: transform.position,
+                direction,
+                max_distance: MAX_PICK_DISTANCE
+            };
+
+            if let Some(hit) = self.ctx.get::<dyn Raycaster>().cast_world(&pointer_ray) {
+                pointer_target = Some(placement_voxel(&pointer_ray, &hit));
+            }
+        }
+
+        if let Some(target) = pointer_target {
+            if pick_start.pressed {
+                self.start = Some(target);
+                self.notice = None;
+            }
+            else if pick_end.pressed {
+                self.end = Some(target);
+            }
+        }
+
+        let uses_end_point = self.parameters.kind.uses_end_point();
+        let span = self.start.map(|start| {
+            let end = self.end.or(pointer_target).unwrap_or(start);
+            (start, [start, end][uses_end_point as usize])
+        });
+
+        if self.draw_gui(&transform, maybe_pointer_direction, span) || generate_shape.pressed {
+            match span {
+                Some((start, end)) if !uses_end_point || self.end.is_some() => self.generate(start, end),
+                Some(_) => self.notice = Some("Pick an end point first".to_string()),
+                None => self.notice = Some("Pick a start point first".to_string())
+            }
+        }
+    }
+
+    /// Registers the set of actions relevant to shape generation, with the player's overrides applied.
+    /// Returns the actions, alongside their bindings so that conflicts may be reported and the actions listed for rebinding.
+    fn get_user_actions(ctx: &mut WingsContextHandle<Self>) -> (UserActions, ActionBindings) {
+        let mut input = ctx.get_mut::<dyn Input>();
+        let mut bindings = ActionBindings::new("Shape generator");
+
+        let generate_shape = bindings.define_digital::<Self>(
+            &mut *input,
+            "Generate shape",
+            "Places the previewed shape using the selected item.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::Return)
+                },
+            ]
+        );
+
+        let pick_end = bindings.define_digital::<Self>(
+            &mut *input,
+            "Pick shape end",
+            "Sets the second point of the shape to the voxel in front of the player's pointer.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::M)
+                },
+            ]
+        );
+
+        let pick_start = bindings.define_digital::<Self>(
+            &mut *input,
+            "Pick shape start",
+            "Sets the first point of the shape to the voxel in front of the player's pointer.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::N)
+                },
+            ]
+        );
+
+        let toggle_shapes_window = bindings.define_digital::<Self>(
+            &mut *input,
+            "Toggle shapes window",
+            "Shows or hides the window for choosing the shape to build and its parameters.",
+            &[
+                DigitalBinding {
+                    threshold: 0.9,
+                    raw_input: RawInput::Key(Key::F5)
+                },
+            ]
+        );
+
+        let actions = UserActions {
+            generate_shape,
+            pick_end,
+            pick_start,
+            toggle_shapes_window
+        };
+
+        (actions, bindings)
+    }
+}
+
+impl WingsSystem for ShapeGenerator {
+    const DEPENDENCIES: Dependencies = dependencies()
+        .with::<dyn ActionRegistry>()
+        .with::<dyn egui::Egui>()
+        .with::<dyn FrameTiming>()
+        .with::<dyn Input>()
+        .with::<dyn ItemPalette>()
+        .with::<dyn Player>()
+        .with::<dyn Raycaster>()
+        .with::<dyn VoxelEditor>();
+
+    const EVENT_HANDLERS: EventHandlers<Self> = event_handlers()
+        .with(Self::handle_shape_input);
+
+    fn new(mut ctx: WingsContextHandle<Self>) -> Self {
+        let (user_actions, bindings) = Self::get_user_actions(&mut ctx);
+        ctx.get_mut::<dyn ActionRegistry>().register_actions(bindings.actions());
+
+        let end = None;
+        let notice = Some(bindings.warnings().join("\n")).filter(|x| !x.is_empty());
+        let open = notice.is_some();
+        let parameters = ShapeParameters::default();
+        let preview = None;
+        let preview_age = 0.0;
+        let projection = ScreenProjection::load();
+        let start = None;
+
+        Self {
+            ctx,
+            end,
+            notice,
+            open,
+            parameters,
+            preview,
+            preview_age,
+            projection,
+            start,
+            user_actions
+        }
+    }
+}
+
+/// A generated shape, alongside the inputs that produced it.
+#[derive(Clone, Debug)]
+struct ShapePreview {
+    /// The parameters and points from which the shape was generated.
+    key: (ShapeParameters, IVec3, IVec3),
+    /// The voxels of the shape, or `None` if it was too large.
+    voxels: Option<Vec<IVec3>>
+}
+
+/// Holds the set of actions relevant to user input.
+#[derive(Copy, Clone, Debug)]
+struct UserActions {
+    /// Places the previewed shape.
+    pub generate_shape: ActionId<Digital>,
+    /// Sets the second point of the shape.
+    pub pick_end: ActionId<Digital>,
+    /// Sets the first point of the shape.
+    pub pick_start: ActionId<Digital>,
+    /// Shows or hides the shapes window.
+    pub toggle_shapes_window: ActionId<Digital>
+}
@@ -0,0 +1,327 @@
+use voxel_engine::math::*;
+
+/// The shapes that may be generated.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ShapeKind {
+    /// A straight line between the two points.
+    #[default]
+    Line,
+    /// An upright wall whose base runs between the two points.
+    Wall,
+    /// An elliptical arch whose feet rest on the two points.
+    Arch,
+    /// A staircase winding upward around the first point.
+    SpiralStairs,
+    /// The upper half of a sphere centered on the first point.
+    Dome,
+    /// An ellipsoid filling the box between the two points.
+    Ellipsoid
+}
+
+impl ShapeKind {
+    /// Every kind of shape, in display order.
+    pub const ALL: [Self; 6] = [Self::Line, Self::Wall, Self::Arch, Self::SpiralStairs, Self::Dome, Self::Ellipsoid];
+
+    /// Gets the human-readable name of the shape.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Line => "Line",
+            Self::Wall => "Wall",
+            Self::Arch => "Arch",
+            Self::SpiralStairs => "Spiral stairs",
+            Self::Dome => "Dome",
+            Self::Ellipsoid => "Ellipsoid"
+        }
+    }
+
+    /// Whether the shape is spanned by two points, rather than placed around a single one.
+    pub fn uses_end_point(self) -> bool {
+        matches!(self, Self::Line | Self::Wall | Self::Arch | Self::Ellipsoid)
+    }
+
+    /// Whether the shape is affected by the height parameter.
+    pub fn uses_height(self) -> bool {
+        matches!(self, Self::Wall | Self::Arch | Self::SpiralStairs)
+    }
+
+    /// Whether the shape is affected by the radius parameter.
+    pub fn uses_radius(self) -> bool {
+        matches!(self, Self::SpiralStairs | Self::Dome)
+    }
+
+    /// Whether the shape is affected by the thickness parameter.
+    pub fn uses_thickness(self) -> bool {
+        matches!(self, Self::Wall | Self::Arch | Self::Dome | Self::Ellipsoid)
+    }
+}
+
+/// Describes a shape and the parameters with which it should be generated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ShapeParameters {
+    /// The height of walls, arches, and staircases.
+    pub height: u32,
+    /// Whether domes and ellipsoids should be shells, rather than solid.
+    pub hollow: bool,
+    /// The kind of shape to generate.
+    pub kind: ShapeKind,
+    /// The radius of domes and staircases.
+    pub radius: u32,
+    /// The number of steps in each full turn of a staircase.
+    pub steps_per_turn: u32,
+    /// The thickness of walls, arches, and shells.
+    pub thickness: u32
+}
+
+impl ShapeParameters {
+    /// The largest number of voxels that a shape may contain.
+    pub const MAX_VOXELS: u64 = 1 << 18;
+
+    /// Gets the voxels of the shape spanned by the given points, or `None` if the shape could be too large.
+    /// The size is checked before generating, so that oversized shapes cost nothing to reject.
+    /// Shapes which are placed around a single point ignore the end point.
+    pub fn voxels(&self, start: IVec3, end: IVec3) -> Option<Vec<IVec3>> {
+        if Self::MAX_VOXELS < self.examined_positions(start, end) {
+            return None;
+        }
+
+        Some(match self.kind {
+            ShapeKind::Line => line(start, end),
+            ShapeKind::Wall => self.wall(start, end),
+            ShapeKind::Arch => self.arch(start, end),
+            ShapeKind::SpiralStairs => self.spiral_stairs(start),
+            ShapeKind::Dome => self.dome(start),
+            ShapeKind::Ellipsoid => self.ellipsoid(start.min(end), start.max(end))
+        })
+    }
+
+    /// Gets the number of positions that generating the shape examines, which bounds the number of voxels in it.
+    fn examined_positions(&self, start: IVec3, end: IVec3) -> u64 {
+        let size = (0..3).map(|x| (end[x] as i64 - start[x] as i64).unsigned_abs() + 1).collect::<Vec<_>>();
+        let diameter = 2 * self.radius as u64 + 1;
+        let height = self.height as u64;
+
+        match self.kind {
+            ShapeKind::Line => size[0].max(size[1]).max(size[2]),
+            ShapeKind::Wall | ShapeKind::Arch => size[0].max(size[2]).saturating_mul(self.thickness.max(1) as u64).saturating_mul(height),
+            ShapeKind::SpiralStairs => diameter.saturating_mul(diameter).saturating_mul(height),
+            ShapeKind::Dome => diameter.saturating_mul(diameter).saturating_mul(self.radius as u64 + 1),
+            ShapeKind::Ellipsoid => size.iter().fold(1, |product, &x| product.saturating_mul(x))
+        }
+    }
+
+    /// Gets the voxels of a wall whose base runs between two points at the height of the first.
+    fn wall(&self, start: IVec3, end: IVec3) -> Vec<IVec3> {
+        let mut result = Vec::new();
+
+        for base in self.thickened_base(start, end).into_iter().flatten() {
+            for y in 0..self.height as i32 {
+                result.push(base + y * IVec3::Y);
+            }
+        }
+
+        result
+    }
+
+    /// Gets the voxels of an elliptical arch whose feet rest on two points at the height of the first.
+    fn arch(&self, start: IVec3, end: IVec3) -> Vec<IVec3> {
+        let bases = self.thickened_base(start, end);
+        let span = bases.first().map(Vec::len).unwrap_or_default();
+
+        let outer = vec2(0.5 * span as f32, self.height as f32 + 0.5);
+        let inner = outer - Vec2::splat(self.thickness as f32);
+        let mut result = Vec::new();
+
+        for row in &bases {
+            for (index, &base) in row.iter().enumerate() {
+                for y in 0..self.height as i32 {
+                    let point = vec2(index as f32 + 0.5 - 0.5 * span as f32, y as f32 + 0.5);
+                    if within_ellipse(point, outer) && !(inner.cmpgt(Vec2::ZERO).all() && (point / inner).length_squared() < 1.0) {
+                        result.push(base + y * IVec3::Y);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Gets the voxels of a staircase that winds around a central column, rising one voxel with each step.
+    fn spiral_stairs(&self, center: IVec3) -> Vec<IVec3> {
+        let radius = self.radius as i32;
+        let step_angle = std::f32::consts::TAU / self.steps_per_turn.max(1) as f32;
+        let mut result = Vec::new();
+
+        for y in 0..self.height as i32 {
+            let step = (y as u32 % self.steps_per_turn.max(1)) as f32;
+            for x in -radius..=radius {
+                for z in -radius..=radius {
+                    let offset = vec2(x as f32, z as f32);
+                    let angle = offset.y.atan2(offset.x).rem_euclid(std::f32::consts::TAU);
+                    let on_step = offset.length() <= radius as f32 + 0.5 && (angle / step_angle).floor() == step;
+
+                    if (x == 0 && z == 0) || on_step {
+                        result.push(center + ivec3(x, y, z));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Gets the voxels of the upper half of a sphere centered on the given voxel.
+    fn dome(&self, center: IVec3) -> Vec<IVec3> {
+        let radius = self.radius as i32;
+        let outer = self.radius as f32 + 0.5;
+        let inner = outer - self.thickness as f32;
+        let mut result = Vec::new();
+
+        for x in -radius..=radius {
+            for y in 0..=radius {
+                for z in -radius..=radius {
+                    let distance = ivec3(x, y, z).as_vec3().length();
+                    if distance <= outer && (!self.hollow || inner <= distance) {
+                        result.push(center + ivec3(x, y, z));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Gets the voxels of the ellipsoid inscribed in the box between two corners.
+    fn ellipsoid(&self, min: IVec3, max: IVec3) -> Vec<IVec3> {
+        let size = max - min + IVec3::ONE;
+        let outer = 0.5 * size.as_vec3();
+        let inner = outer - Vec3::splat(self.thickness as f32);
+        let mut result = Vec::new();
+
+        for x in 0..size.x {
+            for y in 0..size.y {
+                for z in 0..size.z {
+                    let point = ivec3(x, y, z).as_vec3() + Vec3::splat(0.5) - outer;
+                    let in_shell = !self.hollow || inner.cmple(Vec3::ZERO).any() || 1.0 <= (point / inner).length_squared();
+
+                    if (point / outer).length_squared() <= 1.0 && in_shell {
+                        result.push(min + ivec3(x, y, z));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Gets the rows of voxels that form the base of a wall or arch, each running from the first
+    /// point toward the second at the height of the first. There is one row for each voxel of thickness.
+    fn thickened_base(&self, start: IVec3, end: IVec3) -> Vec<Vec<IVec3>> {
+        let end = ivec3(end.x, start.y, end.z);
+        let delta = (end - start).abs();
+        let across = [IVec3::X, IVec3::Z][(delta.z < delta.x) as usize];
+        let thickness = self.thickness.max(1) as i32;
+
+        (-(thickness - 1) / 2..=thickness / 2)
+            .map(|offset| line(start + offset * across, end + offset * across))
+            .collect()
+    }
+}
+
+impl Default for ShapeParameters {
+    fn default() -> Self {
+        Self {
+            height: 8,
+            hollow: true,
+            kind: ShapeKind::default(),
+            radius: 6,
+            steps_per_turn: 16,
+            thickness: 1
+        }
+    }
+}
+
+/// Gets the voxels of a line between two points using the three-dimensional Bresenham algorithm.
+pub fn line(start: IVec3, end: IVec3) -> Vec<IVec3> {
+    let delta = (end - start).abs();
+    let step = (end - start).signum();
+    let driving = (0..3).max_by_key(|&x| delta[x]).unwrap_or_default();
+    let others = [(driving + 1) % 3, (driving + 2) % 3];
+
+    let mut errors = others.map(|x| 2 * delta[x] - delta[driving]);
+    let mut current = start;
+    let mut result = vec![current];
+
+    for _ in 0..delta[driving] {
+        current[driving] += step[driving];
+
+        for (error, axis) in errors.iter_mut().zip(others) {
+            if 0 <= *error {
+                current[axis] += step[axis];
+                *error -= 2 * delta[driving];
+            }
+
+            *error += 2 * delta[axis];
+        }
+
+        result.push(current);
+    }
+
+    result
+}
+
+/// Determines whether a point lies within the axis-aligned ellipse centered on the origin with the given semi-axes.
+fn within_ellipse(point: Vec2, semi_axes: Vec2) -> bool {
+    (point / semi_axes).length_squared() <= 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates parameters for the given kind of shape, with every dimension set to the given size.
+    fn parameters(kind: ShapeKind, size: u32, hollow: bool) -> ShapeParameters {
+        ShapeParameters {
+            height: size,
+            hollow,
+            kind,
+            radius: size,
+            steps_per_turn: 16,
+            thickness: 3
+        }
+    }
+
+    #[test]
+    fn examined_positions_bound_the_voxel_count() {
+        for kind in ShapeKind::ALL {
+            for hollow in [false, true] {
+                let parameters = parameters(kind, 9, hollow);
+                let (start, end) = (ivec3(-3, 5, 2), ivec3(14, -4, 9));
+                let voxels = parameters.voxels(start, end).expect("shape is small enough");
+                assert!(voxels.len() as u64 <= parameters.examined_positions(start, end), "{kind:?} exceeds its bound");
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_shapes_are_rejected() {
+        for kind in ShapeKind::ALL {
+            let parameters = parameters(kind, 100_000, true);
+            assert_eq!(parameters.voxels(IVec3::ZERO, IVec3::splat(1_000_000)), None, "{kind:?} was generated");
+        }
+    }
+
+    #[test]
+    fn extreme_points_do_not_overflow() {
+        let parameters = parameters(ShapeKind::Ellipsoid, 1, false);
+        assert_eq!(parameters.voxels(IVec3::MIN, IVec3::MAX), None);
+    }
+
+    #[test]
+    fn lines_join_their_end_points() {
+        let voxels = line(ivec3(1, 2, 3), ivec3(7, -1, 5));
+        assert_eq!(voxels.first(), Some(&ivec3(1, 2, 3)));
+        assert_eq!(voxels.last(), Some(&ivec3(7, -1, 5)));
+        assert_eq!(voxels.len(), 7);
+        assert!(voxels.windows(2).all(|x| (x[1] - x[0]).abs().max_element() == 1));
+    }
+}
@@ -63,7 +63,7 @@ fn main() {
     let out_dir = current_dir().expect("Failed to get current directory.");
     let out_path = Path::new(&out_dir);
 
-    for mod_name in ["player_controller"] {
+    for mod_name in ["input_recorder", "player_controller", "region_tools", "shape_generator"] {
         build_mod(mod_name, out_path, &mut binary_output, &mut list_output);
     }
 
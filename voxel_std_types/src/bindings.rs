@@ -0,0 +1,601 @@
+use crate::recording::*;
+use serde::*;
+use voxel_engine::*;
+use voxel_engine::input::*;
+use wings::*;
+
+/// Applies the player's binding overrides to the actions that a mod defines, and records the
+/// resulting bindings so that conflicts may be reported and the actions listed for rebinding.
+#[derive(Clone, Debug)]
+pub struct ActionBindings {
+    /// The actions that have been defined, in definition order.
+    actions: Vec<BoundAction>,
+    /// Pairs of actions that are allowed to share inputs, because
+    /// the mod decides between them based upon context.
+    allowed: Vec<(&'static str, &'static str)>,
+    /// The analog actions that have been defined, in definition order.
+    analog_ids: Vec<ActionId<Analog>>,
+    /// The raw inputs that are in effect, alongside the action names.
+    bindings: Vec<(&'static str, RawInput)>,
+    /// The digital actions that have been defined, in definition order.
+    digital_ids: Vec<ActionId<Digital>>,
+    /// The name of the mod that defines the actions.
+    group: &'static str,
+    /// The arrangement of the player's keyboard, by which positional keys are placed.
+    layout: KeyboardLayout,
+    /// A description of the problem encountered while loading the overrides, if any.
+    load_error: Option<String>,
+    /// The bindings that the player has chosen in place of the defaults.
+    overrides: BindingOverrides
+}
+
+impl ActionBindings {
+    /// Loads the player's overrides for the actions of the given mod.
+    pub fn new(group: &'static str) -> Self {
+        let (overrides, load_error) = BindingOverrides::load();
+        let layout = overrides.layout.unwrap_or_default();
+
+        Self {
+            actions: Vec::new(),
+            allowed: Vec::new(),
+            analog_ids: Vec::new(),
+            bindings: Vec::new(),
+            digital_ids: Vec::new(),
+            group,
+            layout,
+            load_error,
+            overrides
+        }
+    }
+
+    /// Marks two actions as intentionally sharing inputs.
+    pub fn allow(&mut self, first: &'static str, second: &'static str) {
+        self.allowed.push((first, second));
+    }
+
+    /// Defines an analog action of the system `S` with the player's overrides applied to its default bindings.
+    /// The name identifies the action both to the engine and in the configuration file.
+    pub fn define_analog<S: WingsSystem>(&mut self, input: &mut dyn Input, name: &'static str, description: &'static str, defaults: &[AnalogBinding]) -> ActionId<Analog> {
+        let bindings = self.analog(name, defaults);
+        let id = input.define(ActionDescriptor::new(ActionName::new::<S>(name), description, &bindings));
+        self.analog_ids.push(id);
+        id
+    }
+
+    /// Defines a digital action of the system `S` with the player's overrides applied to its default bindings.
+    /// The name identifies the action both to the engine and in the configuration file.
+    pub fn define_digital<S: WingsSystem>(&mut self, input: &mut dyn Input, name: &'static str, description: &'static str, defaults: &[DigitalBinding]) -> ActionId<Digital> {
+        let bindings = self.digital(name, defaults);
+        let id = input.define(ActionDescriptor::new(ActionName::new::<S>(name), description, &bindings));
+        self.digital_ids.push(id);
+        id
+    }
+
+    /// Applies any overrides to the bindings of an analog action, and records the result.
+    fn analog(&mut self, name: &'static str, defaults: &[AnalogBinding]) -> Vec<AnalogBinding> {
+        let bindings = defaults.iter().enumerate()
+            .map(|(slot, x)| AnalogBinding { raw_input: self.overrides.get(self.group, name, slot).unwrap_or(self.place(x.raw_input)), ..*x })
+            .collect::<Vec<_>>();
+
+        self.record(name, defaults.iter().map(|x| self.place(x.raw_input)).collect(), bindings.iter().map(|x| x.raw_input));
+        bindings
+    }
+
+    /// Applies any overrides to the bindings of a digital action, and records the result.
+    fn digital(&mut self, name: &'static str, defaults: &[DigitalBinding]) -> Vec<DigitalBinding> {
+        let bindings = defaults.iter().enumerate()
+            .map(|(slot, x)| DigitalBinding { raw_input: self.overrides.get(self.group, name, slot).unwrap_or(self.place(x.raw_input)), ..*x })
+            .collect::<Vec<_>>();
+
+        self.record(name, defaults.iter().map(|x| self.place(x.raw_input)).collect(), bindings.iter().map(|x| x.raw_input));
+        bindings
+    }
+
+    /// Gets the arrangement of the player's keyboard, by which the default keys are placed.
+    pub fn layout(&self) -> KeyboardLayout {
+        self.layout
+    }
+
+    /// Creates a snapshot that captures every action that has been defined, so that
+    /// the mod may read all of its input through it without listing the actions again.
+    pub fn snapshot(&self) -> InputSnapshot {
+        InputSnapshot::new(self.analog_ids.clone(), self.digital_ids.clone())
+    }
+
+    /// Gets every action that has been defined, alongside the actions with which
+    /// it may share inputs, so that it may be listed for rebinding.
+    pub fn actions(&self) -> Vec<BoundAction> {
+        self.actions.iter()
+            .map(|x| BoundAction {
+                shares_inputs_with: self.actions.iter()
+                    .filter(|other| self.is_allowed(&x.name, &other.name))
+                    .map(|other| other.name.clone())
+                    .collect(),
+                ..x.clone()
+            })
+            .collect()
+    }
+
+    /// Produces a warning for each pair of distinct actions that share a raw input, for each action
+    /// allowed to share inputs that was never defined, and for any problem encountered while loading the overrides.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut result = self.load_error.iter().cloned().collect::<Vec<_>>();
+
+        for name in self.allowed.iter().flat_map(|&(first, second)| [first, second]) {
+            if !self.actions.iter().any(|x| x.name == name) {
+                result.push(format!("Action \"{name}\" is allowed to share inputs, but was never defined"));
+            }
+        }
+
+        for (index, &(first, input)) in self.bindings.iter().enumerate() {
+            for &(second, other_input) in &self.bindings[index + 1..] {
+                if first != second && input == other_input && !self.is_allowed(first, second) {
+                    result.push(format!("Actions \"{first}\" and \"{second}\" are both bound to {}", describe_input(input)));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Moves a default key to the same position on the player's keyboard layout. Every default key is placed
+    /// by position, so that defaults which differ on a QWERTY keyboard, including those of different mods, also differ on other layouts.
+    fn place(&self, input: RawInput) -> RawInput {
+        match input {
+            RawInput::Key(key) => RawInput::Key(self.layout.key_at(key)),
+            other => other
+        }
+    }
+
+    /// Records the default and effective inputs of an action.
+    fn record(&mut self, name: &'static str, defaults: Vec<RawInput>, inputs: impl Iterator<Item = RawInput>) {
+        self.bindings.extend(inputs.map(|x| (name, x)));
+        self.actions.push(BoundAction {
+            defaults,
+            group: self.group.to_string(),
+            name: name.to_string(),
+            shares_inputs_with: Vec::new()
+        });
+    }
+
+    /// Determines whether two actions were marked as intentionally sharing inputs.
+    fn is_allowed(&self, first: &str, second: &str) -> bool {
+        self.allowed.iter().any(|&(a, b)| (a == first && b == second) || (a == second && b == first))
+    }
+}
+
+/// An action that a mod has defined, alongside the raw inputs to which it is bound by default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundAction {
+    /// The inputs to which each binding slot of the action is bound, before overrides.
+    pub defaults: Vec<RawInput>,
+    /// The name of the mod that defined the action.
+    pub group: String,
+    /// The name of the action.
+    pub name: String,
+    /// The names of the actions of the same mod that are allowed to share inputs with this one.
+    pub shares_inputs_with: Vec<String>
+}
+
+/// The bindings that the player has chosen in place of the defaults, stored on disk.
+#[derive(Clone, Debug, Default)]
+pub struct BindingOverrides {
+    /// The individual overrides, each of which replaces one binding slot of an action.
+    entries: Vec<BindingOverride>,
+    /// The arrangement of the player's keyboard, or `None` if it has not been chosen or detected.
+    pub layout: Option<KeyboardLayout>
+}
+
+impl BindingOverrides {
+    /// The path of the file in which overrides are stored.
+    pub const CONFIG_PATH: &'static str = "config/bindings.toml";
+
+    /// Loads the overrides from the configuration file. If no file exists, there are no overrides.
+    /// If the file is malformed, no overrides are returned alongside a description of the problem.
+    pub fn load() -> (Self, Option<String>) {
+        match std::fs::read_to_string(Self::CONFIG_PATH) {
+            Ok(text) => match toml::from_str::<BindingConfig>(&text) {
+                Ok(config) => {
+                    let unknown = config.binding.iter().filter(|x| parse_input(&x.input).is_none()).count();
+                    let error = (0 < unknown).then(|| format!("{} contains {unknown} unrecognized inputs", Self::CONFIG_PATH));
+                    (Self { entries: config.binding, layout: config.layout }, error)
+                },
+                Err(error) => (Self::default(), Some(format!("Failed to parse {}: {error}", Self::CONFIG_PATH)))
+            },
+            Err(_) => (Self::default(), None)
+        }
+    }
+
+    /// Writes the overrides to the configuration file.
+    pub fn save(&self) -> Result<(), String> {
+        let config = BindingConfig { binding: self.entries.clone(), layout: self.layout };
+        let text = toml::to_string(&config).map_err(|x| x.to_string())?;
+
+        if let Some(directory) = std::path::Path::new(Self::CONFIG_PATH).parent() {
+            std::fs::create_dir_all(directory).map_err(|x| x.to_string())?;
+        }
+
+        std::fs::write(Self::CONFIG_PATH, text).map_err(|x| x.to_string())
+    }
+
+    /// Gets the input that replaces a binding slot of an action, if any.
+    pub fn get(&self, group: &str, action: &str, slot: usize) -> Option<RawInput> {
+        self.entries.iter()
+            .find(|x| x.group == group && x.action == action && x.slot == slot)
+            .and_then(|x| parse_input(&x.input))
+    }
+
+    /// Replaces a binding slot of an action with the given input. Inputs without a stored
+    /// name are ignored, since they could not be read back from the configuration file.
+    pub fn set(&mut self, group: &str, action: &str, slot: usize, input: RawInput) {
+        let Some(input) = input_name(input) else {
+            return;
+        };
+
+        self.entries.retain(|x| !(x.group == group && x.action == action && x.slot == slot));
+        self.entries.push(BindingOverride {
+            action: action.to_string(),
+            group: group.to_string(),
+            input,
+            slot
+        });
+    }
+
+    /// Removes every override of an action, restoring its default bindings.
+    pub fn reset(&mut self, group: &str, action: &str) {
+        self.entries.retain(|x| !(x.group == group && x.action == action));
+    }
+
+    /// Determines whether any binding slot of an action has been overridden.
+    pub fn is_overridden(&self, group: &str, action: &str) -> bool {
+        self.entries.iter().any(|x| x.group == group && x.action == action)
+    }
+}
+
+/// The arrangements of letters that a keyboard may have. Since the engine only reports keys by the
+/// symbol that they produce, default key bindings are given as positions on a QWERTY keyboard and
+/// translated to the player's layout, so that movement keys stay together and defaults never collide.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout {
+    /// The layout common in English-speaking countries, which is also used when no layout is known.
+    #[default]
+    Qwerty,
+    /// The layout common in France and Belgium.
+    Azerty,
+    /// The layout common in Germany and central Europe.
+    Qwertz,
+    /// The Dvorak simplified layout.
+    Dvorak,
+    /// The Colemak layout.
+    Colemak
+}
+
+impl KeyboardLayout {
+    /// Every layout, in display order.
+    pub const ALL: [Self; 5] = [Self::Qwerty, Self::Azerty, Self::Qwertz, Self::Dvorak, Self::Colemak];
+
+    /// Gets the human-readable name of the layout.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Qwerty => "QWERTY",
+            Self::Azerty => "AZERTY",
+            Self::Qwertz => "QWERTZ",
+            Self::Dvorak => "Dvorak",
+            Self::Colemak => "Colemak"
+        }
+    }
+
+    /// Whether the digit keys produce other symbols unless Shift is held, as on AZERTY keyboards.
+    pub fn shifts_digits(self) -> bool {
+        self == Self::Azerty
+    }
+
+    /// Gets the key in the same position on this layout as the given key on a QWERTY keyboard.
+    /// Keys outside of the letter rows, or whose counterpart cannot be represented, are unchanged.
+    pub fn key_at(self, qwerty: Key) -> Key {
+        let Some(&(symbol, _, _)) = LAYOUT_KEYS.iter().find(|x| x.1 == qwerty) else {
+            return qwerty;
+        };
+
+        Self::Qwerty.rows().iter().zip(self.rows())
+            .find_map(|(qwerty_row, row)| qwerty_row.find(symbol).and_then(|column| row.chars().nth(column)))
+            .and_then(|symbol| LAYOUT_KEYS.iter().find(|x| x.0 == symbol))
+            .map(|x| x.1)
+            .unwrap_or(qwerty)
+    }
+
+    /// Infers the layout from a key press whose symbol differs from the key in that position on a
+    /// QWERTY keyboard. Returns `None` if the press does not distinguish any layout.
+    pub fn detect(logical: egui::Key, physical: egui::Key) -> Option<Self> {
+        let find_key = |key| LAYOUT_KEYS.iter().find(|x| x.2 == key).map(|x| x.1);
+        let (logical, physical) = (find_key(logical)?, find_key(physical)?);
+        (logical != physical).then(|| Self::ALL.into_iter().find(|x| x.key_at(physical) == logical)).flatten()
+    }
+
+    /// Gets the symbols of the three letter rows of the layout, from top to bottom.
+    fn rows(self) -> [&'static str; 3] {
+        match self {
+            Self::Qwerty => ["qwertyuiop", "asdfghjkl;", "zxcvbnm,."],
+            Self::Azerty => ["azertyuiop", "qsdfghjklm", "wxcvbn,;"],
+            Self::Qwertz => ["qwertzuiop", "asdfghjkl", "yxcvbnm,."],
+            Self::Dvorak => ["',.pyfgcrl", "aoeuidhtns", ";qjkxbmwv"],
+            Self::Colemak => ["qwfpgjluy;", "arstdhneio", "zxcvbkm,."]
+        }
+    }
+}
+
+/// Detects gamepad presses, so that they may be bound to actions. Since egui does not observe gamepads,
+/// an action is defined for each gamepad button and axis that may be stored, and these are read instead.
+#[derive(Clone, Debug)]
+pub struct GamepadCapture {
+    /// The actions reading each gamepad axis, alongside the axes.
+    axes: Vec<(ActionId<Analog>, GamepadAxis)>,
+    /// The actions reading each gamepad button, alongside the buttons.
+    buttons: Vec<(ActionId<Digital>, GamepadButton)>
+}
+
+impl GamepadCapture {
+    /// How far an axis must be moved from its center to be captured.
+    const AXIS_THRESHOLD: f32 = 0.5;
+
+    /// Defines the actions of the system `S` that read every gamepad button and axis. These are not listed for rebinding.
+    pub fn new<S: WingsSystem>(input: &mut dyn Input) -> Self {
+        let mut axes = Vec::new();
+        for &(axis, name) in &GAMEPAD_AXES {
+            let binding = AnalogBinding {
+                invert: false,
+                raw_input: RawInput::GamepadAxis(axis)
+            };
+
+            let description = "Detects the gamepad axis while rebinding an action.";
+            axes.push((input.define(ActionDescriptor::new(ActionName::new::<S>(name), description, &[binding])), axis));
+        }
+
+        let mut buttons = Vec::new();
+        for &(button, name) in &GAMEPAD_BUTTONS {
+            let binding = DigitalBinding {
+                threshold: 0.9,
+                raw_input: RawInput::GamepadButton(button)
+            };
+
+            let description = "Detects the gamepad button while rebinding an action.";
+            buttons.push((input.define(ActionDescriptor::new(ActionName::new::<S>(name), description, &[binding])), button));
+        }
+
+        Self {
+            axes,
+            buttons
+        }
+    }
+
+    /// Gets the first gamepad button pressed during this frame, or else the first axis moved far from its center.
+    pub fn capture(&self, input: &dyn Input) -> Option<RawInput> {
+        self.buttons.iter()
+            .find(|x| input.get(x.0).pressed)
+            .map(|x| RawInput::GamepadButton(x.1))
+            .or_else(|| self.axes.iter()
+                .find(|x| Self::AXIS_THRESHOLD < input.get(x.0).abs())
+                .map(|x| RawInput::GamepadAxis(x.1)))
+    }
+}
+
+/// Provides access to the actions of every mod, so that they may be rebound.
+#[system_trait]
+pub trait ActionRegistry: 'static {
+    /// Records actions defined by a mod. Actions of the same mod and name are replaced.
+    fn register_actions(&mut self, actions: Vec<BoundAction>);
+
+    /// Gets all registered actions, in registration order.
+    fn actions(&self) -> Vec<BoundAction>;
+}
+
+/// Gets a short, human-readable name for a raw input.
+pub fn describe_input(input: RawInput) -> String {
+    match input {
+        RawInput::Key(key) => format!("{key:?}"),
+        RawInput::MouseButton(button) => format!("Mouse {button:?}"),
+        RawInput::GamepadButton(button) => format!("Gamepad {button:?}"),
+        RawInput::GamepadAxis(axis) => format!("Gamepad {axis:?}"),
+        #[allow(unreachable_patterns)]
+        other => format!("{other:?}")
+    }
+}
+
+/// Gets the first raw input that is pressed during this frame, so that it may be bound to an action.
+/// Only keys and mouse buttons may be captured, since egui does not observe gamepads; see `GamepadCapture`.
+pub fn capture_input(input_state: &egui::InputState) -> Option<RawInput> {
+    for event in &input_state.events {
+        match *event {
+            egui::Event::Key { key, pressed: true, repeat: false, .. } => {
+                if let Some(&(_, key, _)) = KEYS.iter().find(|x| x.0 == key) {
+                    return Some(RawInput::Key(key));
+                }
+            },
+            egui::Event::PointerButton { button, pressed: true, .. } => {
+                if let Some(&(_, button, _)) = MOUSE_BUTTONS.iter().find(|x| x.0 == button) {
+                    return Some(RawInput::MouseButton(button));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    let modifiers = input_state.modifiers;
+    [modifiers.shift, modifiers.ctrl, modifiers.alt].into_iter()
+        .zip(MODIFIER_KEYS)
+        .find(|x| x.0)
+        .map(|x| RawInput::Key(x.1.0))
+}
+
+/// Gets the name by which a raw input is stored in the configuration file. Unlike the description of an input,
+/// this name does not change between versions, so it may be read back. Returns `None` for inputs that cannot be stored.
+pub fn input_name(input: RawInput) -> Option<String> {
+    stored_inputs().find(|x| x.0 == input).map(|x| x.1)
+}
+
+/// Parses a raw input from the name by which it is stored in the configuration file.
+fn parse_input(text: &str) -> Option<RawInput> {
+    stored_inputs().find(|x| x.1 == text).map(|x| x.0)
+}
+
+/// Gets every raw input that may be stored in the configuration file, alongside its stored name.
+fn stored_inputs() -> impl Iterator<Item = (RawInput, String)> {
+    KEYS.iter().map(|x| (RawInput::Key(x.1), format!("key:{}", x.2)))
+        .chain(MODIFIER_KEYS.iter().map(|x| (RawInput::Key(x.0), format!("key:{}", x.1))))
+        .chain(MOUSE_BUTTONS.iter().map(|x| (RawInput::MouseButton(x.1), format!("mouse:{}", x.2))))
+        .chain(GAMEPAD_BUTTONS.iter().map(|x| (RawInput::GamepadButton(x.0), format!("gamepad:{}", x.1))))
+        .chain(GAMEPAD_AXES.iter().map(|x| (RawInput::GamepadAxis(x.0), format!("axis:{}", x.1))))
+}
+
+/// The keys that may be captured, alongside their egui equivalents and stored names.
+const KEYS: [(egui::Key, Key, &str); 60] = [
+    (egui::Key::A, Key::A, "A"), (egui::Key::B, Key::B, "B"), (egui::Key::C, Key::C, "C"), (egui::Key::D, Key::D, "D"),
+    (egui::Key::E, Key::E, "E"), (egui::Key::F, Key::F, "F"), (egui::Key::G, Key::G, "G"), (egui::Key::H, Key::H, "H"),
+    (egui::Key::I, Key::I, "I"), (egui::Key::J, Key::J, "J"), (egui::Key::K, Key::K, "K"), (egui::Key::L, Key::L, "L"),
+    (egui::Key::M, Key::M, "M"), (egui::Key::N, Key::N, "N"), (egui::Key::O, Key::O, "O"), (egui::Key::P, Key::P, "P"),
+    (egui::Key::Q, Key::Q, "Q"), (egui::Key::R, Key::R, "R"), (egui::Key::S, Key::S, "S"), (egui::Key::T, Key::T, "T"),
+    (egui::Key::U, Key::U, "U"), (egui::Key::V, Key::V, "V"), (egui::Key::W, Key::W, "W"), (egui::Key::X, Key::X, "X"),
+    (egui::Key::Y, Key::Y, "Y"), (egui::Key::Z, Key::Z, "Z"),
+    (egui::Key::Num0, Key::Key0, "0"), (egui::Key::Num1, Key::Key1, "1"), (egui::Key::Num2, Key::Key2, "2"), (egui::Key::Num3, Key::Key3, "3"),
+    (egui::Key::Num4, Key::Key4, "4"), (egui::Key::Num5, Key::Key5, "5"), (egui::Key::Num6, Key::Key6, "6"), (egui::Key::Num7, Key::Key7, "7"),
+    (egui::Key::Num8, Key::Key8, "8"), (egui::Key::Num9, Key::Key9, "9"),
+    (egui::Key::F1, Key::F1, "F1"), (egui::Key::F2, Key::F2, "F2"), (egui::Key::F3, Key::F3, "F3"), (egui::Key::F4, Key::F4, "F4"),
+    (egui::Key::F5, Key::F5, "F5"), (egui::Key::F6, Key::F6, "F6"), (egui::Key::F7, Key::F7, "F7"), (egui::Key::F8, Key::F8, "F8"),
+    (egui::Key::Escape, Key::Escape, "Escape"), (egui::Key::Tab, Key::Tab, "Tab"), (egui::Key::Backspace, Key::Back, "Backspace"), (egui::Key::Enter, Key::Return, "Enter"),
+    (egui::Key::Space, Key::Space, "Space"), (egui::Key::Insert, Key::Insert, "Insert"), (egui::Key::Delete, Key::Delete, "Delete"), (egui::Key::Home, Key::Home, "Home"),
+    (egui::Key::End, Key::End, "End"), (egui::Key::PageUp, Key::PageUp, "PageUp"), (egui::Key::PageDown, Key::PageDown, "PageDown"),
+    (egui::Key::ArrowLeft, Key::Left, "Left"), (egui::Key::ArrowUp, Key::Up, "Up"), (egui::Key::ArrowRight, Key::Right, "Right"), (egui::Key::ArrowDown, Key::Down, "Down"),
+    (egui::Key::Minus, Key::Minus, "Minus")
+];
+
+/// The modifier keys, which egui reports separately from other keys, alongside their stored names.
+const MODIFIER_KEYS: [(Key, &str); 3] = [(Key::LShift, "LeftShift"), (Key::LControl, "LeftControl"), (Key::LAlt, "LeftAlt")];
+
+/// The mouse buttons that may be captured, alongside their egui equivalents and stored names.
+const MOUSE_BUTTONS: [(egui::PointerButton, MouseButton, &str); 3] = [
+    (egui::PointerButton::Primary, MouseButton::Left, "Left"),
+    (egui::PointerButton::Secondary, MouseButton::Right, "Right"),
+    (egui::PointerButton::Middle, MouseButton::Middle, "Middle")
+];
+
+/// The gamepad buttons that may be stored, alongside their stored names.
+const GAMEPAD_BUTTONS: [(GamepadButton, &str); 16] = [
+    (GamepadButton::South, "South"), (GamepadButton::East, "East"), (GamepadButton::North, "North"), (GamepadButton::West, "West"),
+    (GamepadButton::DPadUp, "DPadUp"), (GamepadButton::DPadDown, "DPadDown"), (GamepadButton::DPadLeft, "DPadLeft"), (GamepadButton::DPadRight, "DPadRight"),
+    (GamepadButton::LeftTrigger, "LeftTrigger"), (GamepadButton::RightTrigger, "RightTrigger"),
+    (GamepadButton::LeftTrigger2, "LeftTrigger2"), (GamepadButton::RightTrigger2, "RightTrigger2"),
+    (GamepadButton::LeftThumb, "LeftThumb"), (GamepadButton::RightThumb, "RightThumb"),
+    (GamepadButton::Start, "Start"), (GamepadButton::Select, "Select")
+];
+
+/// The gamepad axes that may be stored, alongside their stored names.
+const GAMEPAD_AXES: [(GamepadAxis, &str); 4] = [
+    (GamepadAxis::LeftStickX, "LeftStickX"), (GamepadAxis::LeftStickY, "LeftStickY"),
+    (GamepadAxis::RightStickX, "RightStickX"), (GamepadAxis::RightStickY, "RightStickY")
+];
+
+/// The symbols of the keys that keyboard layouts may move, alongside their engine and egui equivalents.
+const LAYOUT_KEYS: [(char, Key, egui::Key); 29] = [
+    ('a', Key::A, egui::Key::A), ('b', Key::B, egui::Key::B), ('c', Key::C, egui::Key::C), ('d', Key::D, egui::Key::D),
+    ('e', Key::E, egui::Key::E), ('f', Key::F, egui::Key::F), ('g', Key::G, egui::Key::G), ('h', Key::H, egui::Key::H),
+    ('i', Key::I, egui::Key::I), ('j', Key::J, egui::Key::J), ('k', Key::K, egui::Key::K), ('l', Key::L, egui::Key::L),
+    ('m', Key::M, egui::Key::M), ('n', Key::N, egui::Key::N), ('o', Key::O, egui::Key::O), ('p', Key::P, egui::Key::P),
+    ('q', Key::Q, egui::Key::Q), ('r', Key::R, egui::Key::R), ('s', Key::S, egui::Key::S), ('t', Key::T, egui::Key::T),
+    ('u', Key::U, egui::Key::U), ('v', Key::V, egui::Key::V), ('w', Key::W, egui::Key::W), ('x', Key::X, egui::Key::X),
+    ('y', Key::Y, egui::Key::Y), ('z', Key::Z, egui::Key::Z),
+    (',', Key::Comma, egui::Key::Comma), ('.', Key::Period, egui::Key::Period), (';', Key::Semicolon, egui::Key::Semicolon)
+];
+
+/// A single override, replacing one binding slot of an action.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BindingOverride {
+    /// The name of the action.
+    action: String,
+    /// The name of the mod that defined the action.
+    group: String,
+    /// The raw input that replaces the default.
+    input: String,
+    /// The index of the replaced binding among the action's bindings.
+    slot: usize
+}
+
+/// The on-disk representation of the binding overrides.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct BindingConfig {
+    /// The overrides, in the order in which they were made.
+    #[serde(default)]
+    binding: Vec<BindingOverride>,
+    /// The arrangement of the player's keyboard, if it has been chosen or detected.
+    #[serde(default)]
+    layout: Option<KeyboardLayout>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_names_round_trip() {
+        for (input, name) in stored_inputs() {
+            assert_eq!(input_name(input).as_deref(), Some(name.as_str()));
+            assert_eq!(parse_input(&name), Some(input));
+        }
+    }
+
+    #[test]
+    fn qwerty_keys_are_unchanged() {
+        for &(_, key, _) in &LAYOUT_KEYS {
+            assert_eq!(KeyboardLayout::Qwerty.key_at(key), key);
+        }
+    }
+
+    #[test]
+    fn keys_move_to_the_same_position() {
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::W), Key::Z);
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::A), Key::Q);
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::Q), Key::A);
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::M), Key::Comma);
+        assert_eq!(KeyboardLayout::Qwertz.key_at(Key::Y), Key::Z);
+        assert_eq!(KeyboardLayout::Qwertz.key_at(Key::Z), Key::Y);
+        assert_eq!(KeyboardLayout::Dvorak.key_at(Key::W), Key::Comma);
+        assert_eq!(KeyboardLayout::Dvorak.key_at(Key::S), Key::O);
+        assert_eq!(KeyboardLayout::Colemak.key_at(Key::S), Key::R);
+        assert_eq!(KeyboardLayout::Colemak.key_at(Key::D), Key::S);
+    }
+
+    #[test]
+    fn unrepresentable_keys_are_unchanged() {
+        assert_eq!(KeyboardLayout::Dvorak.key_at(Key::Q), Key::Q);
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::Period), Key::Period);
+        assert_eq!(KeyboardLayout::Azerty.key_at(Key::Key1), Key::Key1);
+    }
+
+    #[test]
+    fn distinct_keys_stay_distinct() {
+        for layout in KeyboardLayout::ALL {
+            for (index, &(_, first, _)) in LAYOUT_KEYS.iter().enumerate() {
+                for &(_, second, _) in &LAYOUT_KEYS[index + 1..] {
+                    let (first_placed, second_placed) = (layout.key_at(first), layout.key_at(second));
+                    let unchanged = first_placed == first || second_placed == second;
+                    assert!(first_placed != second_placed || unchanged, "{first:?} and {second:?} collide on {layout:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layouts_are_detected() {
+        assert_eq!(KeyboardLayout::detect(egui::Key::Z, egui::Key::W), Some(KeyboardLayout::Azerty));
+        assert_eq!(KeyboardLayout::detect(egui::Key::Z, egui::Key::Y), Some(KeyboardLayout::Qwertz));
+        assert_eq!(KeyboardLayout::detect(egui::Key::O, egui::Key::S), Some(KeyboardLayout::Dvorak));
+        assert_eq!(KeyboardLayout::detect(egui::Key::R, egui::Key::S), Some(KeyboardLayout::Colemak));
+    }
+
+    #[test]
+    fn undistinguishing_presses_are_not_detected() {
+        assert_eq!(KeyboardLayout::detect(egui::Key::A, egui::Key::A), None);
+        assert_eq!(KeyboardLayout::detect(egui::Key::Space, egui::Key::Space), None);
+        assert_eq!(KeyboardLayout::detect(egui::Key::Enter, egui::Key::Space), None);
+    }
+}
@@ -0,0 +1,20 @@
+use voxel_engine::math::*;
+use wings::*;
+
+/// Applies voxel edits on behalf of other mods, so that edits made
+/// by all standard tools are tracked in a single place.
+#[system_trait]
+pub trait VoxelEditor: 'static {
+    /// Empties each of the given voxels.
+    fn delete_voxels(&mut self, voxels: Vec<IVec3>);
+
+    /// Gets the known material of each of the given voxels, in order. The engine does not expose voxel
+    /// contents to mods, so only voxels that were edited through this system have a known material.
+    fn materials_at(&self, voxels: Vec<IVec3>) -> Vec<Option<u32>>;
+
+    /// Fills each voxel with its paired material, as a single operation.
+    fn place_materials(&mut self, voxels: Vec<(IVec3, u32)>);
+
+    /// Fills each of the given voxels with a material.
+    fn place_voxels(&mut self, voxels: Vec<IVec3>, material: u32);
+}
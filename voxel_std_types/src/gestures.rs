@@ -0,0 +1,295 @@
+use crate::recording::*;
+use voxel_engine::input::*;
+
+/// Recognizes a way of pressing a digital action, such as a chord, a long press, or a double-tap,
+/// so that mods may respond to inputs which a single binding cannot express. A gesture must be
+/// updated once every frame, since its timing is measured across frames.
+#[derive(Clone, Debug)]
+pub struct Gesture {
+    /// The actions that prevent the gesture while any of them is held.
+    excluded: Vec<ActionId<Digital>>,
+    /// The actions that must be held alongside the trigger.
+    modifiers: Vec<ActionId<Digital>>,
+    /// Tracks the timing of the trigger's presses.
+    progress: GestureProgress,
+    /// The action whose presses are recognized.
+    trigger: ActionId<Digital>
+}
+
+impl Gesture {
+    /// Creates a gesture that fires as soon as the trigger is pressed.
+    pub fn press(trigger: ActionId<Digital>) -> Self {
+        Self::new(trigger, GestureTiming::Press)
+    }
+
+    /// Creates a gesture that fires once the trigger has been held for the given number of seconds.
+    pub fn hold(trigger: ActionId<Digital>, seconds: f32) -> Self {
+        Self::new(trigger, GestureTiming::Hold(seconds))
+    }
+
+    /// Creates a gesture that fires when the trigger is released within the given number of seconds of being pressed.
+    pub fn tap(trigger: ActionId<Digital>, seconds: f32) -> Self {
+        Self::new(trigger, GestureTiming::Tap(seconds))
+    }
+
+    /// Creates a gesture that fires when the trigger is pressed twice within the given number of seconds.
+    pub fn double_tap(trigger: ActionId<Digital>, seconds: f32) -> Self {
+        Self::new(trigger, GestureTiming::DoubleTap(seconds))
+    }
+
+    /// Requires the given actions to be held when the trigger is pressed, and for as long as the
+    /// gesture lasts. This turns the gesture into a chord, such as `Ctrl + Z`.
+    pub fn with_modifiers(mut self, modifiers: &[ActionId<Digital>]) -> Self {
+        self.modifiers.extend_from_slice(modifiers);
+        self
+    }
+
+    /// Prevents the gesture while any of the given actions is held, so that a plain
+    /// binding does not also fire when its key is used as part of a chord.
+    pub fn without(mut self, excluded: &[ActionId<Digital>]) -> Self {
+        self.excluded.extend_from_slice(excluded);
+        self
+    }
+
+    /// Gets how the trigger must be pressed.
+    pub fn timing(&self) -> GestureTiming {
+        self.progress.timing
+    }
+
+    /// Forgets any press in progress, as though the trigger had never been pressed.
+    pub fn reset(&mut self) {
+        self.progress.reset();
+    }
+
+    /// Reads the actions for this frame, and determines whether the gesture occurred.
+    /// The actions may be read from the engine directly, or from a captured frame.
+    pub fn update(&mut self, input: &(impl ActionSource + ?Sized), delta_time: f32) -> GestureState {
+        let trigger = input.digital(self.trigger);
+        let modifiers_held = self.modifiers.iter().all(|&x| input.digital(x).held)
+            && !self.excluded.iter().any(|&x| input.digital(x).held);
+
+        self.progress.advance(trigger, modifiers_held, delta_time)
+    }
+
+    /// Creates a gesture without modifiers.
+    fn new(trigger: ActionId<Digital>, timing: GestureTiming) -> Self {
+        Self {
+            excluded: Vec::new(),
+            modifiers: Vec::new(),
+            progress: GestureProgress::new(timing),
+            trigger
+        }
+    }
+}
+
+/// Tracks how the trigger of a gesture has been pressed over time, independently of where its state is read.
+#[derive(Copy, Clone, Debug)]
+struct GestureProgress {
+    /// Whether the current press of the trigger completed a double-tap.
+    completed: bool,
+    /// The time for which the trigger has been held, in seconds, or `None` if it is not being held as part of the gesture.
+    held_for: Option<f32>,
+    /// The time since the trigger was first tapped, in seconds, while a double-tap may still be completed.
+    since_tap: Option<f32>,
+    /// How the trigger must be pressed.
+    timing: GestureTiming
+}
+
+impl GestureProgress {
+    /// Begins tracking a trigger that has not yet been pressed.
+    fn new(timing: GestureTiming) -> Self {
+        Self {
+            completed: false,
+            held_for: None,
+            since_tap: None,
+            timing
+        }
+    }
+
+    /// Forgets any press in progress.
+    fn reset(&mut self) {
+        *self = Self::new(self.timing);
+    }
+
+    /// Advances by one frame, given the state of the trigger and whether the modifiers allow the gesture.
+    /// Determines whether the gesture occurred.
+    fn advance(&mut self, trigger: DigitalSample, modifiers_held: bool, delta_time: f32) -> GestureState {
+        let previous = self.held_for;
+        self.held_for = if trigger.pressed && modifiers_held {
+            Some(0.0)
+        }
+        else if trigger.held && modifiers_held {
+            previous.map(|x| x + delta_time)
+        }
+        else {
+            None
+        };
+
+        let started = trigger.pressed && self.held_for.is_some();
+        let released_after = previous.filter(|_| !trigger.held);
+
+        match self.timing {
+            GestureTiming::Press => GestureState {
+                held: self.held_for.is_some(),
+                pressed: started
+            },
+            GestureTiming::Hold(seconds) => {
+                let reached = self.held_for.is_some_and(|x| seconds <= x);
+                GestureState {
+                    held: reached,
+                    pressed: reached && !previous.is_some_and(|x| seconds <= x)
+                }
+            },
+            GestureTiming::Tap(seconds) => {
+                let tapped = released_after.is_some_and(|x| x < seconds);
+                GestureState {
+                    held: false,
+                    pressed: tapped
+                }
+            },
+            GestureTiming::DoubleTap(seconds) => {
+                self.since_tap = self.since_tap.map(|x| x + delta_time).filter(|&x| x <= seconds);
+                let completed = started && self.since_tap.is_some();
+                if started {
+                    self.since_tap = [Some(0.0), None][completed as usize];
+                }
+
+                self.completed = completed || (self.completed && self.held_for.is_some());
+                GestureState {
+                    held: self.completed,
+                    pressed: completed
+                }
+            }
+        }
+    }
+}
+
+/// Describes how the trigger of a gesture must be pressed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GestureTiming {
+    /// The trigger is pressed.
+    Press,
+    /// The trigger is held for at least the given number of seconds.
+    Hold(f32),
+    /// The trigger is released within the given number of seconds of being pressed.
+    Tap(f32),
+    /// The trigger is pressed twice within the given number of seconds.
+    DoubleTap(f32)
+}
+
+/// Whether a gesture occurred during a frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GestureState {
+    /// Whether the gesture is complete and its trigger is still held. Taps are never held.
+    pub held: bool,
+    /// Whether the gesture was completed during this frame.
+    pub pressed: bool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The duration of each simulated frame, in seconds.
+    const FRAME: f32 = 0.1;
+    /// The trigger during the frame in which it is pressed.
+    const PRESS: DigitalSample = DigitalSample { held: true, pressed: true };
+    /// The trigger while it remains held.
+    const HOLD: DigitalSample = DigitalSample { held: true, pressed: false };
+    /// The trigger while it is released.
+    const RELEASE: DigitalSample = DigitalSample { held: false, pressed: false };
+
+    /// Advances through the given frames with the modifiers held, and returns the frames in which the gesture was pressed.
+    fn pressed_frames(timing: GestureTiming, frames: &[DigitalSample]) -> Vec<usize> {
+        let mut progress = GestureProgress::new(timing);
+        frames.iter().enumerate()
+            .filter(|&(_, &trigger)| progress.advance(trigger, true, FRAME).pressed)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    #[test]
+    fn presses_fire_immediately() {
+        let mut progress = GestureProgress::new(GestureTiming::Press);
+        assert_eq!(progress.advance(PRESS, true, FRAME), GestureState { held: true, pressed: true });
+        assert_eq!(progress.advance(HOLD, true, FRAME), GestureState { held: true, pressed: false });
+        assert_eq!(progress.advance(RELEASE, true, FRAME), GestureState::default());
+    }
+
+    #[test]
+    fn resets_forget_presses_in_progress() {
+        let mut progress = GestureProgress::new(GestureTiming::DoubleTap(0.25));
+        progress.advance(PRESS, true, FRAME);
+        progress.advance(RELEASE, true, FRAME);
+        progress.reset();
+        assert_eq!(progress.advance(PRESS, true, FRAME), GestureState::default());
+    }
+
+    #[test]
+    fn presses_require_modifiers() {
+        let mut progress = GestureProgress::new(GestureTiming::Press);
+        assert_eq!(progress.advance(PRESS, false, FRAME), GestureState::default());
+        assert_eq!(progress.advance(HOLD, true, FRAME), GestureState::default());
+    }
+
+    #[test]
+    fn holds_fire_once_at_the_threshold() {
+        let frames = [PRESS, HOLD, HOLD, HOLD, HOLD, HOLD, HOLD, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::Hold(0.35), &frames), vec![4]);
+    }
+
+    #[test]
+    fn holds_released_early_do_not_fire() {
+        let frames = [PRESS, HOLD, RELEASE, PRESS, HOLD, HOLD, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::Hold(0.35), &frames), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn holds_stop_when_modifiers_are_released() {
+        let mut progress = GestureProgress::new(GestureTiming::Hold(0.05));
+        progress.advance(PRESS, true, FRAME);
+        assert!(progress.advance(HOLD, true, FRAME).pressed);
+        assert_eq!(progress.advance(HOLD, false, FRAME), GestureState::default());
+        assert_eq!(progress.advance(HOLD, true, FRAME), GestureState::default());
+    }
+
+    #[test]
+    fn taps_fire_on_release_before_the_timeout() {
+        let frames = [PRESS, HOLD, RELEASE, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::Tap(0.25), &frames), vec![2]);
+    }
+
+    #[test]
+    fn taps_held_past_the_timeout_do_not_fire() {
+        let frames = [PRESS, HOLD, HOLD, HOLD, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::Tap(0.25), &frames), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn double_taps_fire_on_the_second_press_within_the_window() {
+        let frames = [PRESS, RELEASE, PRESS, HOLD, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::DoubleTap(0.25), &frames), vec![2]);
+    }
+
+    #[test]
+    fn double_taps_outside_the_window_do_not_fire() {
+        let frames = [PRESS, RELEASE, RELEASE, RELEASE, PRESS, RELEASE];
+        assert_eq!(pressed_frames(GestureTiming::DoubleTap(0.25), &frames), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn double_taps_restart_after_completing() {
+        let frames = [PRESS, RELEASE, PRESS, RELEASE, PRESS, RELEASE, PRESS];
+        assert_eq!(pressed_frames(GestureTiming::DoubleTap(0.25), &frames), vec![2, 6]);
+    }
+
+    #[test]
+    fn double_taps_are_held_until_released() {
+        let mut progress = GestureProgress::new(GestureTiming::DoubleTap(0.25));
+        progress.advance(PRESS, true, FRAME);
+        progress.advance(RELEASE, true, FRAME);
+        assert_eq!(progress.advance(PRESS, true, FRAME), GestureState { held: true, pressed: true });
+        assert_eq!(progress.advance(HOLD, true, FRAME), GestureState { held: true, pressed: false });
+        assert_eq!(progress.advance(RELEASE, true, FRAME), GestureState::default());
+    }
+}
@@ -0,0 +1,16 @@
+/// Applies the player's binding overrides and lists the actions of every mod.
+pub mod bindings;
+/// Allows mods to edit voxels through a shared system.
+pub mod editing;
+/// Composes digital actions into chords, holds, taps, and double-taps.
+pub mod gestures;
+/// Helpers for drawing world-space overlays with egui.
+pub mod overlay;
+/// Types for describing the items that a player may place.
+pub mod palette;
+/// Captures the input read each frame, so that sessions may be recorded and replayed.
+pub mod recording;
+/// Types for describing the physics objects that a player may spawn.
+pub mod spawning;
+/// Helpers for determining which voxels the player is aiming at.
+pub mod targeting;
@@ -0,0 +1,184 @@
+use crate::targeting::*;
+use serde::*;
+use voxel_engine::*;
+use voxel_engine::math::*;
+
+/// Projects world-space geometry onto the screen so that it may be drawn with egui.
+/// The engine does not expose its camera projection to mods, so the field of view is measured by
+/// comparing the pointer's screen position with its world-space direction. Since a locked pointer
+/// stays at the center of the screen, where nothing can be measured, the last measurement is saved
+/// so that it is shared between mods and used from the start of later sessions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ScreenProjection {
+    /// The measured vertical field of view, in radians, or `None` if it has not been measured.
+    vertical_fov: Option<f32>
+}
+
+impl ScreenProjection {
+    /// The path of the file in which the measured field of view is stored.
+    pub const CONFIG_PATH: &'static str = "config/projection.toml";
+    /// The vertical field of view that is assumed until it has been measured.
+    const DEFAULT_VERTICAL_FOV: f32 = std::f32::consts::FRAC_PI_3;
+    /// The minimum screen distance from the center at which the pointer is used for measurement.
+    const MIN_CALIBRATION_OFFSET: f32 = 16.0;
+    /// The depth in front of the camera at which geometry is clipped.
+    const NEAR_PLANE: f32 = 0.05;
+    /// The change in the measured field of view, in radians, beyond which the new measurement is saved.
+    const SAVE_THRESHOLD: f32 = 0.01;
+
+    /// Loads the field of view measured during an earlier session or by another mod. If none has been saved,
+    /// or the file is malformed, the default field of view is used until the pointer is unlocked and moved.
+    pub fn load() -> Self {
+        let vertical_fov = std::fs::read_to_string(Self::CONFIG_PATH).ok()
+            .and_then(|text| toml::from_str::<ProjectionConfig>(&text).ok())
+            .map(|config| config.vertical_fov)
+            .filter(|&x| 0.0 < x && x < std::f32::consts::PI);
+
+        Self { vertical_fov }
+    }
+
+    /// Measures the field of view using the pointer's screen position and its world-space direction,
+    /// and saves the measurement if it changed. The pointer must be away from the center of the screen.
+    /// Failures to save are ignored, since the measurement is repeated whenever the pointer is unlocked.
+    pub fn calibrate(&mut self, camera: &Transform, pointer_direction: Vec3A, pointer_position: egui::Pos2, screen: egui::Rect) {
+        let offset = pointer_position - screen.center();
+        let direction = camera.rotation.inverse() * pointer_direction;
+
+        if Self::MIN_CALIBRATION_OFFSET < offset.length() && Self::NEAR_PLANE < direction.z {
+            let tangent = direction.xy().length() / direction.z;
+            if 0.0 < tangent {
+                let focal_length = offset.length() / tangent;
+                let vertical_fov = 2.0 * (0.5 * screen.height() / focal_length).atan();
+
+                if !self.vertical_fov.is_some_and(|x| (x - vertical_fov).abs() < Self::SAVE_THRESHOLD) {
+                    self.vertical_fov = Some(vertical_fov);
+                    let _ = self.save();
+                }
+            }
+        }
+    }
+
+    /// Projects a world-space point onto the screen, if it lies in front of the camera.
+    pub fn project(&self, camera: &Transform, screen: egui::Rect, point: Vec3A) -> Option<egui::Pos2> {
+        let local = Self::to_camera_space(camera, point);
+        (Self::NEAR_PLANE <= local.z).then(|| self.project_local(screen, local))
+    }
+
+    /// Draws the outline of an axis-aligned box between two world-space corners.
+    pub fn draw_box_outline(&self, painter: &egui::Painter, camera: &Transform, min: Vec3A, max: Vec3A, stroke: egui::Stroke) {
+        let corners = Self::box_corners(camera, min, max);
+        let screen = painter.clip_rect();
+
+        for (a, b) in BOX_EDGES {
+            if let Some((start, end)) = Self::clip_segment(corners[a], corners[b]) {
+                painter.line_segment([self.project_local(screen, start), self.project_local(screen, end)], stroke);
+            }
+        }
+    }
+
+    /// Draws a line between two world-space points.
+    pub fn draw_line(&self, painter: &egui::Painter, camera: &Transform, start: Vec3A, end: Vec3A, stroke: egui::Stroke) {
+        let screen = painter.clip_rect();
+        if let Some((start, end)) = Self::clip_segment(Self::to_camera_space(camera, start), Self::to_camera_space(camera, end)) {
+            painter.line_segment([self.project_local(screen, start), self.project_local(screen, end)], stroke);
+        }
+    }
+
+    /// Fills the camera-facing sides of an axis-aligned box between two world-space corners.
+    pub fn draw_box_faces(&self, painter: &egui::Painter, camera: &Transform, min: Vec3A, max: Vec3A, fill: egui::Color32) {
+        let corners = Self::box_corners(camera, min, max);
+        let eye = to_voxel_space(camera.position);
+        let screen = painter.clip_rect();
+
+        for (normal, face) in BOX_FACES {
+            let center = 0.5 * (min + max);
+            let extent = 0.5 * (max - min);
+            let face_center = center + normal * extent;
+
+            if 0.0 < (eye - face_center).dot(normal) && face.iter().all(|&x| Self::NEAR_PLANE <= corners[x].z) {
+                let points = face.iter().map(|&x| self.project_local(screen, corners[x])).collect();
+                painter.add(egui::Shape::convex_polygon(points, fill, egui::Stroke::NONE));
+            }
+        }
+    }
+
+    /// Gets the camera-space positions of the eight corners of a box.
+    fn box_corners(camera: &Transform, min: Vec3A, max: Vec3A) -> [Vec3A; 8] {
+        std::array::from_fn(|index| {
+            let corner = vec3a(
+                [min.x, max.x][index & 1],
+                [min.y, max.y][(index >> 1) & 1],
+                [min.z, max.z][(index >> 2) & 1]
+            );
+            Self::to_camera_space(camera, corner)
+        })
+    }
+
+    /// Clips a camera-space line segment against the near plane.
+    fn clip_segment(start: Vec3A, end: Vec3A) -> Option<(Vec3A, Vec3A)> {
+        match (Self::NEAR_PLANE <= start.z, Self::NEAR_PLANE <= end.z) {
+            (true, true) => Some((start, end)),
+            (false, false) => None,
+            (start_visible, _) => {
+                let t = (Self::NEAR_PLANE - start.z) / (end.z - start.z);
+                let clipped = start.lerp(end, t);
+                Some(if start_visible { (start, clipped) } else { (clipped, end) })
+            }
+        }
+    }
+
+    /// Gets the distance from the eye to the screen plane, in points, for the given screen.
+    fn focal_length(&self, screen: egui::Rect) -> f32 {
+        0.5 * screen.height() / (0.5 * self.vertical_fov.unwrap_or(Self::DEFAULT_VERTICAL_FOV)).tan()
+    }
+
+    /// Writes the measured field of view to the configuration file.
+    fn save(&self) -> Result<(), String> {
+        let Some(vertical_fov) = self.vertical_fov else {
+            return Ok(());
+        };
+
+        let text = toml::to_string(&ProjectionConfig { vertical_fov }).map_err(|x| x.to_string())?;
+        if let Some(directory) = std::path::Path::new(Self::CONFIG_PATH).parent() {
+            std::fs::create_dir_all(directory).map_err(|x| x.to_string())?;
+        }
+
+        std::fs::write(Self::CONFIG_PATH, text).map_err(|x| x.to_string())
+    }
+
+    /// Projects a camera-space point that lies in front of the near plane onto the screen.
+    fn project_local(&self, screen: egui::Rect, local: Vec3A) -> egui::Pos2 {
+        let focal_length = self.focal_length(screen);
+        screen.center() + egui::vec2(focal_length * local.x / local.z, -focal_length * local.y / local.z)
+    }
+
+    /// Transforms a world-space point into the camera's coordinate frame,
+    /// where `x` points right, `y` points up, and `z` points forward.
+    fn to_camera_space(camera: &Transform, point: Vec3A) -> Vec3A {
+        camera.rotation.inverse() * (point - to_voxel_space(camera.position))
+    }
+}
+
+/// The on-disk representation of the measured projection.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+struct ProjectionConfig {
+    /// The vertical field of view, in radians.
+    vertical_fov: f32
+}
+
+/// Pairs of corner indices forming the twelve edges of a box.
+const BOX_EDGES: [(usize, usize); 12] = [
+    (0, 1), (2, 3), (4, 5), (6, 7),
+    (0, 2), (1, 3), (4, 6), (5, 7),
+    (0, 4), (1, 5), (2, 6), (3, 7)
+];
+
+/// The outward normal and corner indices of the six faces of a box.
+const BOX_FACES: [(Vec3A, [usize; 4]); 6] = [
+    (Vec3A::NEG_X, [0, 2, 6, 4]),
+    (Vec3A::X, [1, 5, 7, 3]),
+    (Vec3A::NEG_Y, [0, 4, 5, 1]),
+    (Vec3A::Y, [2, 3, 7, 6]),
+    (Vec3A::NEG_Z, [0, 1, 3, 2]),
+    (Vec3A::Z, [4, 6, 7, 5])
+];
@@ -0,0 +1,30 @@
+use serde::*;
+use wings::*;
+
+/// Describes an item that the player may select and place into the world.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteEntry {
+    /// The color used to represent this item in the user interface.
+    pub color: [u8; 4],
+    /// A short glyph displayed alongside the item name, if any.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The engine material ID that is placed when using this item.
+    pub material: u32,
+    /// The human-readable name of the item.
+    pub name: String
+}
+
+/// Provides access to the set of items that the player may select.
+#[system_trait]
+pub trait ItemPalette: 'static {
+    /// Adds an entry to the palette. If an entry with the same
+    /// material already exists, it is replaced.
+    fn add_entry(&mut self, entry: PaletteEntry);
+
+    /// Gets all entries in the palette, in display order.
+    fn entries(&self) -> Vec<PaletteEntry>;
+
+    /// Gets the entry that the player currently has selected, if any.
+    fn selected_entry(&self) -> Option<PaletteEntry>;
+}
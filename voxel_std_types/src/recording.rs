@@ -0,0 +1,159 @@
+use crate::bindings::*;
+use serde::*;
+use std::time::*;
+use voxel_engine::input::*;
+use voxel_engine::math::*;
+use wings::*;
+
+/// The state of a digital action during a single frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigitalSample {
+    /// Whether the action is being held.
+    pub held: bool,
+    /// Whether the action began during this frame.
+    pub pressed: bool
+}
+
+/// Everything that a mod read from the engine's input during a single frame.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputFrame {
+    /// The value of each analog action, in the order in which the mod listed them.
+    pub analog: Vec<f32>,
+    /// The duration of the frame, in seconds.
+    pub delta_time: f32,
+    /// The state of each digital action, in the order in which the mod listed them.
+    pub digital: Vec<DigitalSample>,
+    /// How far the pointer moved during the frame.
+    pub pointer_delta: [f32; 2],
+    /// The direction in which the pointer is aiming, if any.
+    pub pointer_direction: Option<[f32; 3]>,
+    /// How many steps the scroll wheel moved during the frame.
+    pub scroll_delta: [i32; 2]
+}
+
+/// A source of action values, which may be the engine's input or a captured frame.
+pub trait ActionSource {
+    /// Gets the value of an analog action.
+    fn analog(&self, action: ActionId<Analog>) -> f32;
+
+    /// Gets the state of a digital action.
+    fn digital(&self, action: ActionId<Digital>) -> DigitalSample;
+}
+
+impl ActionSource for dyn Input {
+    fn analog(&self, action: ActionId<Analog>) -> f32 {
+        self.get(action)
+    }
+
+    fn digital(&self, action: ActionId<Digital>) -> DigitalSample {
+        let state = self.get(action);
+        DigitalSample {
+            held: state.held,
+            pressed: state.pressed
+        }
+    }
+}
+
+/// Captures the input that a mod reads once at the start of each frame, so that the frame may
+/// be passed through an `InputRecorder` and every later read is served from the same values.
+/// This allows a recorded session to be replayed exactly, as long as it was driven only by those actions and the
+/// pointer. Touch input and egui interactions are read from the egui context, and are not captured.
+#[derive(Clone, Debug)]
+pub struct InputSnapshot {
+    /// The analog actions that are captured, in frame order.
+    analog: Vec<ActionId<Analog>>,
+    /// The digital actions that are captured, in frame order.
+    digital: Vec<ActionId<Digital>>,
+    /// The values for the current frame.
+    frame: InputFrame,
+    /// The sum of the frame durations seen so far.
+    time: Duration
+}
+
+impl InputSnapshot {
+    /// Creates a snapshot of the given actions. Actions that are not listed read as released.
+    pub fn new(analog: Vec<ActionId<Analog>>, digital: Vec<ActionId<Digital>>) -> Self {
+        Self {
+            analog,
+            digital,
+            frame: InputFrame::default(),
+            time: Duration::ZERO
+        }
+    }
+
+    /// Reads the listed actions and pointer state from the engine.
+    pub fn capture(&self, input: &dyn Input, delta_time: f32) -> InputFrame {
+        let pointer_delta = input.pointer_delta();
+        let scroll_delta: IVec2 = input.scroll_delta();
+
+        InputFrame {
+            analog: self.analog.iter().map(|&x| input.analog(x)).collect(),
+            delta_time,
+            digital: self.digital.iter().map(|&x| input.digital(x)).collect(),
+            pointer_delta: pointer_delta.to_array(),
+            pointer_direction: input.pointer_direction().map(|x| x.to_array()),
+            scroll_delta: scroll_delta.to_array()
+        }
+    }
+
+    /// Uses the given frame for every read until the next frame is set.
+    pub fn set_frame(&mut self, frame: InputFrame) {
+        self.time += Duration::from_secs_f32(frame.delta_time.max(0.0));
+        self.frame = frame;
+    }
+
+    /// Gets the duration of the current frame, in seconds.
+    pub fn delta_time(&self) -> f32 {
+        self.frame.delta_time
+    }
+
+    /// Gets the sum of the frame durations up to and including the current frame.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Gets how far the pointer moved during the current frame.
+    pub fn pointer_delta(&self) -> Vec2 {
+        Vec2::from_array(self.frame.pointer_delta)
+    }
+
+    /// Gets the direction in which the pointer is aiming, if any.
+    pub fn pointer_direction(&self) -> Option<Vec3A> {
+        self.frame.pointer_direction.map(Vec3A::from_array)
+    }
+
+    /// Gets how many steps the scroll wheel moved during the current frame.
+    pub fn scroll_delta(&self) -> IVec2 {
+        IVec2::from_array(self.frame.scroll_delta)
+    }
+}
+
+impl ActionSource for InputSnapshot {
+    fn analog(&self, action: ActionId<Analog>) -> f32 {
+        self.analog.iter().position(|&x| x == action)
+            .and_then(|x| self.frame.analog.get(x).copied())
+            .unwrap_or_default()
+    }
+
+    fn digital(&self, action: ActionId<Digital>) -> DigitalSample {
+        self.digital.iter().position(|&x| x == action)
+            .and_then(|x| self.frame.digital.get(x).copied())
+            .unwrap_or_default()
+    }
+}
+
+/// Records the input that mods read each frame, or replays a recorded session in its place.
+#[system_trait]
+pub trait InputRecorder: 'static {
+    /// Passes a frame of input read by the given mod through the recorder. While recording, the frame
+    /// is stored and returned unchanged. During playback, the next recorded frame for the mod is returned instead.
+    fn process_frame(&mut self, source: String, frame: InputFrame) -> InputFrame;
+
+    /// Identifies the current recording or replay. This changes whenever one begins, so that mods may
+    /// return the state that their input does not reproduce to its defaults before the first frame.
+    fn session(&self) -> u32;
+
+    /// Gets the recorder's own actions. Since the mods that list actions for rebinding may depend upon the
+    /// recorder, it cannot register them itself, so they are provided here to be registered on its behalf.
+    fn actions(&self) -> Vec<BoundAction>;
+}
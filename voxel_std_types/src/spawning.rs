@@ -0,0 +1,27 @@
+use serde::*;
+use wings::*;
+
+/// Describes a kind of physics object that the player may spawn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnableObject {
+    /// The color of the swatch that previews this object in the user interface.
+    pub color: [u8; 4],
+    /// A short glyph displayed alongside the object name, if any.
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The engine object kind that is spawned.
+    pub kind: u32,
+    /// The human-readable name of the object.
+    pub name: String
+}
+
+/// Provides access to the set of physics objects that the player may spawn.
+#[system_trait]
+pub trait ObjectCatalog: 'static {
+    /// Adds an object to the catalog. If an object with the same
+    /// kind already exists, it is replaced.
+    fn add_object(&mut self, object: SpawnableObject);
+
+    /// Gets all objects in the catalog, in display order.
+    fn objects(&self) -> Vec<SpawnableObject>;
+}
@@ -0,0 +1,115 @@
+use voxel_engine::math::*;
+use voxel_engine::physics::*;
+
+/// Converts a world-space position into a vector of voxel coordinates.
+pub fn to_voxel_space(position: WorldVec) -> Vec3A {
+    Vec3A::from(position)
+}
+
+/// Computes the outward normal of the voxel face through which the ray entered the voxel.
+/// This is the face of the voxel's bounding box whose slab the ray crosses last.
+pub fn hit_face_normal(ray: &Ray, voxel: IVec3) -> IVec3 {
+    let origin = to_voxel_space(ray.position);
+    let min = voxel.as_vec3a();
+    let mut best_axis = 0;
+    let mut best_distance = f32::NEG_INFINITY;
+
+    for axis in 0..3 {
+        if ray.direction[axis] != 0.0 {
+            let face = min[axis] + [1.0, 0.0][(0.0 < ray.direction[axis]) as usize];
+            let distance = (face - origin[axis]) / ray.direction[axis];
+            if best_distance < distance {
+                best_axis = axis;
+                best_distance = distance;
+            }
+        }
+    }
+
+    let mut normal = IVec3::ZERO;
+    normal[best_axis] = [1, -1][(0.0 < ray.direction[best_axis]) as usize];
+    normal
+}
+
+/// Gets the empty voxel adjacent to the hit face, into which new voxels should be placed.
+pub fn placement_voxel(ray: &Ray, hit: &RaycastHit) -> IVec3 {
+    hit.voxel + hit_face_normal(ray, hit.voxel)
+}
+
+/// Determines whether the given voxel overlaps a sphere around the provided position.
+pub fn voxel_intersects_point(voxel: IVec3, position: Vec3A, radius: f32) -> bool {
+    let min = voxel.as_vec3a();
+    let closest = position.clamp(min, min + Vec3A::ONE);
+    closest.distance_squared(position) < radius * radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The voxel struck by the rays in these tests.
+    const VOXEL: IVec3 = ivec3(2, -1, 5);
+
+    /// Creates a ray that starts at the given offset from the center of the struck voxel and points toward its center.
+    fn ray_from(offset: Vec3A) -> Ray {
+        Ray {
+            position: (VOXEL.as_vec3a() + Vec3A::splat(0.5) + offset).into(),
+            direction: -offset.normalize(),
+            max_distance: 256.0
+        }
+    }
+
+    /// Gets the point at which the ray enters the struck voxel.
+    fn entry_point(ray: &Ray) -> Vec3A {
+        let origin = to_voxel_space(ray.position);
+        let min = VOXEL.as_vec3a();
+        let distance = (0..3)
+            .map(|axis| (min[axis] + [1.0, 0.0][(0.0 < ray.direction[axis]) as usize] - origin[axis]) / ray.direction[axis])
+            .filter(|x| x.is_finite())
+            .fold(f32::NEG_INFINITY, f32::max);
+        origin + distance * ray.direction
+    }
+
+    #[test]
+    fn each_face_is_identified() {
+        for normal in [IVec3::X, IVec3::NEG_X, IVec3::Y, IVec3::NEG_Y, IVec3::Z, IVec3::NEG_Z] {
+            let ray = ray_from(3.0 * normal.as_vec3a());
+            assert_eq!(hit_face_normal(&ray, VOXEL), normal);
+        }
+    }
+
+    #[test]
+    fn oblique_rays_use_the_face_they_cross_last() {
+        assert_eq!(hit_face_normal(&ray_from(vec3a(3.0, 2.5, 0.0)), VOXEL), IVec3::X);
+        assert_eq!(hit_face_normal(&ray_from(vec3a(2.5, 3.0, 0.0)), VOXEL), IVec3::Y);
+        assert_eq!(hit_face_normal(&ray_from(vec3a(-0.2, -1.0, 3.0)), VOXEL), IVec3::Z);
+        assert_eq!(hit_face_normal(&ray_from(vec3a(-2.0, -1.9, -1.8)), VOXEL), IVec3::NEG_X);
+        assert_eq!(hit_face_normal(&ray_from(vec3a(1.8, -2.0, 1.9)), VOXEL), IVec3::NEG_Y);
+    }
+
+    #[test]
+    fn placement_voxel_is_the_one_the_ray_leaves_before_the_hit() {
+        for offset in [vec3a(3.0, 2.5, 0.0), vec3a(-0.2, -1.0, 3.0), vec3a(1.8, -2.0, 1.9), vec3a(0.0, 0.0, -4.0)] {
+            let ray = ray_from(offset);
+            let before_entry = entry_point(&ray) - 0.01 * ray.direction;
+            assert_eq!(VOXEL + hit_face_normal(&ray, VOXEL), before_entry.floor().as_ivec3());
+        }
+    }
+
+    #[test]
+    fn edges_and_corners_place_against_a_face_toward_the_ray() {
+        let edges_and_corners = [
+            vec3a(2.0, 2.0, 0.0),
+            vec3a(-2.0, 0.0, 2.0),
+            vec3a(0.0, -2.0, -2.0),
+            vec3a(2.0, 2.0, 2.0),
+            vec3a(-2.0, -2.0, 2.0),
+            vec3a(-2.0, 2.0, -2.0)
+        ];
+
+        for offset in edges_and_corners {
+            let normal = hit_face_normal(&ray_from(offset), VOXEL);
+            assert_eq!(normal.abs().element_sum(), 1);
+            assert!(0.0 < normal.as_vec3a().dot(offset));
+        }
+    }
+}